@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use crate::errors::ReceiveError;
+
+/// Fields of a received CCTP burn message that this program acts on.
+pub struct BurnMessage {
+    pub source_domain: u32,
+    pub destination_domain: u32,
+    /// Message nonce, left-padded to 32 bytes for CCTP v1.
+    pub nonce: [u8; 32],
+    pub mint_recipient: Pubkey,
+    /// Amount minted to `mint_recipient`, net of any fee charged by CCTP.
+    pub amount: u64,
+}
+
+// Message header offsets (CCTP v1)
+const SOURCE_DOMAIN_INDEX: usize = 4;
+const DESTINATION_DOMAIN_INDEX: usize = 8;
+const NONCE_INDEX: usize = 12;
+const MESSAGE_BODY_INDEX: usize = 116;
+
+// Message header offsets (CCTP v2)
+const NONCE_V2_INDEX: usize = 12;
+const MESSAGE_BODY_V2_INDEX: usize = 148;
+
+// Burn message body offsets (shared by v1 and v2)
+const MINT_RECIPIENT_INDEX: usize = 36;
+const AMOUNT_INDEX: usize = 68;
+const BURN_MESSAGE_LEN: usize = 132;
+
+// Burn message body offsets (CCTP v2 only)
+const FEE_EXECUTED_INDEX: usize = 164;
+const BURN_MESSAGE_V2_LEN: usize = 228;
+
+impl BurnMessage {
+    pub fn parse_v1(message: &[u8]) -> Result<Self> {
+        require!(message.len() >= MESSAGE_BODY_INDEX + BURN_MESSAGE_LEN, ReceiveError::InvalidMessage);
+        let body = &message[MESSAGE_BODY_INDEX..];
+
+        let mut nonce = [0; 32];
+        nonce[24..].copy_from_slice(&message[NONCE_INDEX..NONCE_INDEX + 8]);
+
+        Ok(Self {
+            source_domain: read_u32(message, SOURCE_DOMAIN_INDEX),
+            destination_domain: read_u32(message, DESTINATION_DOMAIN_INDEX),
+            nonce,
+            mint_recipient: read_pubkey(body, MINT_RECIPIENT_INDEX),
+            amount: read_u256_as_u64(body, AMOUNT_INDEX)?,
+        })
+    }
+
+    pub fn parse_v2(message: &[u8]) -> Result<Self> {
+        require!(message.len() >= MESSAGE_BODY_V2_INDEX + BURN_MESSAGE_V2_LEN, ReceiveError::InvalidMessage);
+        let body = &message[MESSAGE_BODY_V2_INDEX..];
+
+        let mut nonce = [0; 32];
+        nonce.copy_from_slice(&message[NONCE_V2_INDEX..NONCE_V2_INDEX + 32]);
+
+        let amount = read_u256_as_u64(body, AMOUNT_INDEX)?;
+        let fee_executed = read_u256_as_u64(body, FEE_EXECUTED_INDEX)?;
+
+        Ok(Self {
+            source_domain: read_u32(message, SOURCE_DOMAIN_INDEX),
+            destination_domain: read_u32(message, DESTINATION_DOMAIN_INDEX),
+            nonce,
+            mint_recipient: read_pubkey(body, MINT_RECIPIENT_INDEX),
+            amount: amount.checked_sub(fee_executed).ok_or(ReceiveError::InvalidMessage)?,
+        })
+    }
+}
+
+fn read_u32(data: &[u8], index: usize) -> u32 {
+    u32::from_be_bytes(data[index..index + 4].try_into().unwrap())
+}
+
+fn read_pubkey(data: &[u8], index: usize) -> Pubkey {
+    Pubkey::new_from_array(data[index..index + 32].try_into().unwrap())
+}
+
+fn read_u256_as_u64(data: &[u8], index: usize) -> Result<u64> {
+    // amounts are big-endian uint256, but USDC amounts always fit in the low 8 bytes
+    require!(data[index..index + 24].iter().all(|b| *b == 0), ReceiveError::InvalidMessage);
+    Ok(u64::from_be_bytes(data[index + 24..index + 32].try_into().unwrap()))
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+/// Account context to invoke [receive_message], including the accounts forwarded to the CCTP
+/// Token Messenger Minter program's `handle_receive_message`.
+pub struct ReceiveMessage<'info> {
+    //#[account(mut, signer)]
+    pub payer: AccountInfo<'info>,
+
+    /// Signer. Must match the destination caller of the message, unless it is unrestricted.
+    //#[account(signer)]
+    pub caller: AccountInfo<'info>,
+
+    /// Seeds must be \["message_transmitter_authority", receiver\] (CCTP Message Transmitter
+    /// program).
+    pub authority_pda: AccountInfo<'info>,
+
+    /// Seeds must be \["message_transmitter"\] (CCTP Message Transmitter program).
+    pub message_transmitter: AccountInfo<'info>,
+
+    /// Mutable. Nonce bitmap covering the message nonce (CCTP Message Transmitter program).
+    //#[account(mut)]
+    pub used_nonces: AccountInfo<'info>,
+
+    /// CCTP Token Messenger Minter program.
+    pub receiver: AccountInfo<'info>,
+
+    pub system_program: AccountInfo<'info>,
+
+    /// Seeds must be \["__event_authority"\] (CCTP Message Transmitter program).
+    pub event_authority: AccountInfo<'info>,
+
+    /// CCTP Message Transmitter program.
+    pub message_transmitter_program: AccountInfo<'info>,
+
+    /// Seeds must be \["token_messenger"\] (CCTP Token Messenger Minter program).
+    pub token_messenger: AccountInfo<'info>,
+
+    /// Seeds must be \["remote_token_messenger"\, remote_domain.to_string()] (CCTP Token Messenger
+    /// Minter program).
+    pub remote_token_messenger: AccountInfo<'info>,
+
+    /// Mutable. Seeds must be \["token_minter"\] (CCTP Token Messenger Minter program).
+    //#[account(mut)]
+    pub token_minter: AccountInfo<'info>,
+
+    /// Mutable. Seeds must be \["local_token", mint\] (CCTP Token Messenger Minter program).
+    //#[account(mut)]
+    pub local_token: AccountInfo<'info>,
+
+    /// Seeds must be \["token_pair", remote_domain.to_string(), remote_token\] (CCTP Token
+    /// Messenger Minter program).
+    pub token_pair: AccountInfo<'info>,
+
+    /// Mutable. Token account receiving the minted tokens.
+    //#[account(mut)]
+    pub recipient_token_account: AccountInfo<'info>,
+
+    /// Mutable. Seeds must be \["custody", mint\] (CCTP Token Messenger Minter program).
+    //#[account(mut)]
+    pub custody_token_account: AccountInfo<'info>,
+
+    pub token_program: AccountInfo<'info>,
+
+    /// Seeds must be \["__event_authority"\] (CCTP Token Messenger Minter program).
+    pub token_messenger_event_authority: AccountInfo<'info>,
+}
+
+/// Parameters to invoke [receive_message].
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ReceiveMessageParams {
+    /// Raw CCTP message.
+    pub message: Vec<u8>,
+
+    /// Circle attestation of `message`.
+    pub attestation: Vec<u8>,
+}
+
+impl<'info> ToAccountMetas for ReceiveMessage<'info> {
+    fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.payer.key(), true),
+            AccountMeta::new_readonly(self.caller.key(), true),
+            AccountMeta::new_readonly(self.authority_pda.key(), false),
+            AccountMeta::new_readonly(self.message_transmitter.key(), false),
+            AccountMeta::new(self.used_nonces.key(), false),
+            AccountMeta::new_readonly(self.receiver.key(), false),
+            AccountMeta::new_readonly(self.system_program.key(), false),
+            AccountMeta::new_readonly(self.event_authority.key(), false),
+            AccountMeta::new_readonly(self.message_transmitter_program.key(), false),
+            AccountMeta::new_readonly(self.token_messenger.key(), false),
+            AccountMeta::new_readonly(self.remote_token_messenger.key(), false),
+            AccountMeta::new(self.token_minter.key(), false),
+            AccountMeta::new(self.local_token.key(), false),
+            AccountMeta::new_readonly(self.token_pair.key(), false),
+            AccountMeta::new(self.recipient_token_account.key(), false),
+            AccountMeta::new(self.custody_token_account.key(), false),
+            AccountMeta::new_readonly(self.token_program.key(), false),
+            AccountMeta::new_readonly(self.token_messenger_event_authority.key(), false),
+            AccountMeta::new_readonly(self.receiver.key(), false),
+        ]
+    }
+}
+
+impl<'info> ToAccountInfos<'info> for ReceiveMessage<'info> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![
+            self.payer.clone(),
+            self.caller.clone(),
+            self.authority_pda.clone(),
+            self.message_transmitter.clone(),
+            self.used_nonces.clone(),
+            self.receiver.clone(),
+            self.system_program.clone(),
+            self.event_authority.clone(),
+            self.message_transmitter_program.clone(),
+            self.token_messenger.clone(),
+            self.remote_token_messenger.clone(),
+            self.token_minter.clone(),
+            self.local_token.clone(),
+            self.token_pair.clone(),
+            self.recipient_token_account.clone(),
+            self.custody_token_account.clone(),
+            self.token_program.clone(),
+            self.token_messenger_event_authority.clone(),
+        ]
+    }
+}
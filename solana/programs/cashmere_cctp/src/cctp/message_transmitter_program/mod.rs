@@ -0,0 +1,3 @@
+pub mod cpi;
+
+// anchor_lang::declare_id!(crate::cctp::MESSAGE_TRANSMITTER_PROGRAM_ID);
@@ -0,0 +1,2 @@
+mod receive_message;
+pub use receive_message::*;
@@ -1,18 +1,64 @@
 pub mod token_messenger_minter_program;
 pub mod token_messenger_minter_program_v2;
+pub mod message_transmitter_program;
+pub mod message_transmitter_program_v2;
+pub mod message;
 
 use anchor_lang::solana_program::{pubkey, pubkey::Pubkey};
 
-pub const MESSAGE_TRANSMITTER_PROGRAM_ID: Pubkey =
-    pubkey!("CCTPmbSD7gX1bxKPAmg77w8oFzNFpaQiQUWD43TKaecd");
-pub const TOKEN_MESSENGER_MINTER_PROGRAM_ID: Pubkey =
-    pubkey!("CCTPiPYPc6AsJuwueEnWgSgucamXDZwBd53dQ11YiKX3");
-pub const MESSAGE_TRANSMITTER_V2_PROGRAM_ID: Pubkey =
-    pubkey!("CCTPV2Sm4AdWt5296sk4P66VBZ7bEhcARwFaaS9YPbeC");
-pub const TOKEN_MESSENGER_MINTER_V2_PROGRAM_ID: Pubkey =
-    pubkey!("CCTPV2vPZJS2u2BBsUoscuikbYjnpFmbFsvVuJdgUMQe");
-pub const USDC_MINT: Pubkey =
-    pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+pub use network::*;
+
+#[cfg(feature = "mainnet")]
+mod network {
+    use super::*;
+
+    pub const LOCAL_DOMAIN: u32 = 5;
+    pub const MESSAGE_TRANSMITTER_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPmbSD7gX1bxKPAmg77w8oFzNFpaQiQUWD43TKaecd");
+    pub const TOKEN_MESSENGER_MINTER_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPiPYPc6AsJuwueEnWgSgucamXDZwBd53dQ11YiKX3");
+    pub const MESSAGE_TRANSMITTER_V2_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPV2Sm4AdWt5296sk4P66VBZ7bEhcARwFaaS9YPbeC");
+    pub const TOKEN_MESSENGER_MINTER_V2_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPV2vPZJS2u2BBsUoscuikbYjnpFmbFsvVuJdgUMQe");
+    pub const USDC_MINT: Pubkey =
+        pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+}
+
+#[cfg(feature = "devnet")]
+mod network {
+    use super::*;
+
+    pub const LOCAL_DOMAIN: u32 = 5;
+    pub const MESSAGE_TRANSMITTER_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPmbSD7gX1bxKPAmg77w8oFzNFpaQiQUWD43TKaecd");
+    pub const TOKEN_MESSENGER_MINTER_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPiPYPc6AsJuwueEnWgSgucamXDZwBd53dQ11YiKX3");
+    pub const MESSAGE_TRANSMITTER_V2_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPV2Sm4AdWt5296sk4P66VBZ7bEhcARwFaaS9YPbeC");
+    pub const TOKEN_MESSENGER_MINTER_V2_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPV2vPZJS2u2BBsUoscuikbYjnpFmbFsvVuJdgUMQe");
+    pub const USDC_MINT: Pubkey =
+        pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+}
+
+// localnet runs against a fork with the mainnet CCTP programs and USDC mint cloned in
+#[cfg(feature = "localnet")]
+mod network {
+    use super::*;
+
+    pub const LOCAL_DOMAIN: u32 = 5;
+    pub const MESSAGE_TRANSMITTER_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPmbSD7gX1bxKPAmg77w8oFzNFpaQiQUWD43TKaecd");
+    pub const TOKEN_MESSENGER_MINTER_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPiPYPc6AsJuwueEnWgSgucamXDZwBd53dQ11YiKX3");
+    pub const MESSAGE_TRANSMITTER_V2_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPV2Sm4AdWt5296sk4P66VBZ7bEhcARwFaaS9YPbeC");
+    pub const TOKEN_MESSENGER_MINTER_V2_PROGRAM_ID: Pubkey =
+        pubkey!("CCTPV2vPZJS2u2BBsUoscuikbYjnpFmbFsvVuJdgUMQe");
+    pub const USDC_MINT: Pubkey =
+        pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+}
 
 #[macro_export]
 macro_rules! impl_anchor_account_readonly {
@@ -72,6 +72,22 @@ pub struct DepositForBurnParams {
     pub mint_recipient: [u8; 32],
 }
 
+/// Parameters to invoke [deposit_for_burn_with_caller].
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct DepositForBurnWithCallerParams {
+    /// Transfer (burn) amount.
+    pub amount: u64,
+
+    /// CCTP domain value of the token to be transferred.
+    pub destination_domain: u32,
+
+    /// Recipient of assets on target network.
+    pub mint_recipient: [u8; 32],
+
+    /// Only this caller can mint tokens on behalf of `mint_recipient` on the target network.
+    pub destination_caller: [u8; 32],
+}
+
 // // CPI call to invoke the CCTP Token Messenger Minter program to burn Circle-supported assets.
 // //
 // // NOTE: This instruction requires specifying a specific caller on the destination network. Only
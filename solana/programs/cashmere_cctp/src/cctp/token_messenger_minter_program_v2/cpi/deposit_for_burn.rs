@@ -81,6 +81,28 @@ pub struct DepositForBurnParams {
     pub min_finality_threshold: u32,
 }
 
+/// Parameters to invoke `deposit_for_burn_with_hook`.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct DepositForBurnWithHookParams {
+    /// Transfer (burn) amount.
+    pub amount: u64,
+
+    /// CCTP domain value of the token to be transferred.
+    pub destination_domain: u32,
+
+    /// Recipient of assets on target network.
+    pub mint_recipient: [u8; 32],
+
+    pub destination_caller: [u8; 32],
+
+    pub max_fee: u64,
+
+    pub min_finality_threshold: u32,
+
+    /// Arbitrary data forwarded to the hook executor on the target network.
+    pub hook_data: Vec<u8>,
+}
+
 // // CPI call to invoke the CCTP Token Messenger Minter program to burn Circle-supported assets.
 // //
 // // NOTE: This instruction requires specifying a specific caller on the destination network. Only
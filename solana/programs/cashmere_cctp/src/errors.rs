@@ -17,6 +17,10 @@ pub enum SignatureVerificationError {
     InvalidMessageData,
     #[msg("Invalid signer")]
     InvalidSignature,
+    #[msg("Not enough signatures from the signer set")]
+    ThresholdNotMet,
+    #[msg("Ed25519 instruction index does not precede the current instruction")]
+    InvalidInstructionIndex,
 }
 
 #[error_code]
@@ -34,6 +38,59 @@ pub enum TransferError {
     NativeAmountTooLow,
     #[msg("Contract is paused")]
     Paused,
+    #[msg("Quote has not expired yet")]
+    QuoteNotExpired,
+    #[msg("Destination domain is disabled")]
+    DomainDisabled,
+    #[msg("CCTP version not allowed for destination domain")]
+    CctpVersionNotAllowed,
+    #[msg("Fee below domain minimum")]
+    FeeBelowMinimum,
+    #[msg("Hook data is empty or too large")]
+    InvalidHookData,
+    #[msg("Payer is not the delegate of the owner token account")]
+    RelayerNotDelegate,
+    #[msg("Relayed transfers must pay fees in USDC")]
+    NativeFeeNotRelayable,
+    #[msg("Relayer fee account missing")]
+    MissingRelayerFeeAccount,
+    #[msg("Partner payout account missing or not the partner's")]
+    InvalidPartnerPayout,
+    #[msg("Transfer amount below minimum")]
+    AmountBelowMinimum,
+    #[msg("Transfer amount above maximum")]
+    AmountAboveMaximum,
+    #[msg("Global outflow limit exceeded")]
+    GlobalRateLimitExceeded,
+    #[msg("Destination domain outflow limit exceeded")]
+    DomainRateLimitExceeded,
+    #[msg("Sender is blocked")]
+    SenderBlocked,
+    #[msg("Sender is not on the allowlist")]
+    SenderNotAllowed,
+    #[msg("CCTP v1 transfers are paused")]
+    CctpV1Paused,
+    #[msg("CCTP v2 transfers are paused")]
+    CctpV2Paused,
+    #[msg("Native fee payments are paused")]
+    NativeFeePaused,
+    #[msg("USDC fee payments are paused")]
+    UsdcFeePaused,
+    #[msg("Gas drops are paused")]
+    GasDropPaused,
+    #[msg("Used quote account is not the marker of the signed quote")]
+    InvalidUsedQuote,
+    #[msg("Quote already used")]
+    QuoteAlreadyUsed,
+}
+
+#[error_code]
+#[derive(Eq, PartialEq)]
+pub enum ReceiveError {
+    #[msg("Invalid CCTP message")]
+    InvalidMessage,
+    #[msg("Recipient token account does not match the message")]
+    InvalidRecipient,
 }
 
 #[error_code]
@@ -44,4 +101,50 @@ pub enum ParamError {
     AdminUnauthorized,
     #[msg("Invalid domain")]
     InvalidDomain,
+    #[msg("Invalid CCTP versions")]
+    InvalidCctpVersions,
+    #[msg("No pending owner")]
+    NoPendingOwner,
+    #[msg("Only the pending owner can accept ownership")]
+    NotPendingOwner,
+    #[msg("Invalid signer set or threshold")]
+    InvalidSignerSet,
+    #[msg("Withdrawal exceeds the vault balance")]
+    InsufficientVaultBalance,
+    #[msg("Partner share too high")]
+    PartnerShareTooHigh,
+    #[msg("Invalid fee tiers or percentage fee bounds")]
+    InvalidFeeTiers,
+    #[msg("Minimum transfer amount above maximum")]
+    InvalidAmountLimits,
+    #[msg("Sender list account missing or not the sender's entry")]
+    InvalidSenderEntry,
+    #[msg("Timelock delay too long")]
+    InvalidTimelockDelay,
+    #[msg("Pending change cannot be executed before its eta")]
+    TimelockNotExpired,
+    #[msg("Config account is not a Cashmere CCTP config")]
+    InvalidConfigAccount,
+    #[msg("Config account is already at the current version")]
+    ConfigAlreadyMigrated,
+    #[msg("Invalid signer key or rotation overlap")]
+    InvalidSignerKey,
+    #[msg("Change must be queued through the timelock")]
+    TimelockRequired,
+    #[msg("Partner account missing or not the partner of the change")]
+    InvalidPartner,
+}
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Price account missing or not the configured oracle")]
+    InvalidOracle,
+    #[msg("Invalid price account")]
+    InvalidPriceAccount,
+    #[msg("Price unavailable")]
+    PriceUnavailable,
+    #[msg("Price is stale")]
+    StalePrice,
+    #[msg("Price confidence interval too wide")]
+    PriceConfidenceTooWide,
 }
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::{ConfigChange, DomainConfigParams, DomainFees, Lane, Role, SenderList};
 
 #[event]
 pub struct TransferEvent {
@@ -8,8 +9,250 @@ pub struct TransferEvent {
     pub solana_owner: [u8; 32],
     pub user: Pubkey,
     pub amount: u64,
-    pub gas_drop_amount: u64,
+    pub gas_drop_amount: u64, // charged, in lamports when `fee_is_native`
     pub fee_is_native: bool,
     pub cctp_nonce: i64,
     pub cctp_message: Pubkey,
+    pub hook_data_hash: [u8; 32],
+    pub relayer: Pubkey, // default unless relayed
+    pub partner: Pubkey, // default without partner
+}
+
+#[event]
+pub struct ReceiveEvent {
+    pub source_domain: u32,
+    pub source_nonce: u64, // `nonce` of the `TransferEvent` on the source chain
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub gas_drop_amount: u64,
+    pub cctp_nonce: [u8; 32],
+    pub cctp_version: u8,
+}
+
+#[event]
+pub struct OwnershipTransferProposed {
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnershipProposalCancelled {
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnershipTransferred {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct PausedChanged {
+    pub old_paused: bool,
+    pub new_paused: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct LanePausedChanged {
+    pub lane: Lane,
+    pub old_paused: bool,
+    pub new_paused: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct SignerKeyChanged {
+    pub old_signer_key: [u8; 32],
+    pub new_signer_key: [u8; 32],
+    pub previous_signer_key_expiry: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct SignerSetChanged {
+    pub old_signers: Vec<[u8; 32]>,
+    pub new_signers: Vec<[u8; 32]>,
+    pub old_threshold: u8,
+    pub new_threshold: u8,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct GasDropCollectorChanged {
+    pub old_gas_drop_collector_sol: Pubkey,
+    pub new_gas_drop_collector_sol: Pubkey,
+    pub old_gas_drop_collector_usdc: Pubkey,
+    pub new_gas_drop_collector_usdc: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum GasDropLimit {
+    Usdc,
+    Native,
+    Receive,
+}
+
+#[event]
+pub struct GasDropLimitChanged {
+    pub limit: GasDropLimit,
+    pub old_limit: u64,
+    pub new_limit: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct RequireFullQuoteChanged {
+    pub old_require_full_quote: bool,
+    pub new_require_full_quote: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct RoleChanged {
+    pub role: Role,
+    pub old_holder: Pubkey,
+    pub new_holder: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct GasVaultWithdrawn {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DomainConfigCreated {
+    pub domain: u32,
+    pub params: DomainConfigParams,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DomainConfigChanged {
+    pub domain: u32,
+    pub old_params: DomainConfigParams,
+    pub new_params: DomainConfigParams,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DomainFeesChanged {
+    pub domain: u32,
+    pub old_fees: DomainFees,
+    pub new_fees: DomainFees,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DomainEnabledChanged {
+    pub domain: u32,
+    pub old_enabled: bool,
+    pub new_enabled: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct FeeWithdrawn {
+    pub usdc_amount: u64,
+    pub native_amount: u64,
+    pub usdc_destination: Pubkey,
+    pub native_destination: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct PartnerChanged {
+    pub partner: Pubkey,
+    pub old_share_bp: u64,
+    pub new_share_bp: u64,
+    pub old_payout: Pubkey,
+    pub new_payout: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct GasDropOracleChanged {
+    pub old_oracle: Pubkey,
+    pub new_oracle: Pubkey,
+    pub max_price_age: u64,
+    pub max_confidence_bp: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TransferAmountLimitsChanged {
+    pub old_min_transfer_amount: u64,
+    pub new_min_transfer_amount: u64,
+    pub old_max_transfer_amount: u64,
+    pub new_max_transfer_amount: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct RateLimitChanged {
+    pub old_capacity: u64,
+    pub new_capacity: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct SenderListChanged {
+    pub list: SenderList,
+    pub sender: Pubkey,
+    pub listed: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AllowlistOnlyChanged {
+    pub old_allowlist_only: bool,
+    pub new_allowlist_only: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub eta: u64,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub executor: Pubkey,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TimelockDelayChanged {
+    pub old_timelock_delay: u64,
+    pub new_timelock_delay: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ConfigMigrated {
+    pub old_version: u8,
+    pub new_version: u8,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct LegacyFeeCollectorsDropped {
+    pub fee_collector_sol: Pubkey,
+    pub fee_collector_usdc: Pubkey,
 }
@@ -1,55 +1,236 @@
-use anchor_lang::prelude::*;
-use crate::state::Config;
+use anchor_lang::{
+    prelude::*,
+    system_program,
+};
+use crate::state::{Config, GasVault, Lane, Role};
 use crate::errors::ParamError;
+use crate::events::{
+    OwnershipTransferProposed,
+    OwnershipProposalCancelled,
+    OwnershipTransferred,
+    PausedChanged,
+    LanePausedChanged,
+    GasDropLimit,
+    GasDropLimitChanged,
+    RequireFullQuoteChanged,
+    GasDropOracleChanged,
+    TransferAmountLimitsChanged,
+    AllowlistOnlyChanged,
+    RoleChanged,
+    GasVaultWithdrawn,
+};
 
 pub fn set_paused_ix(ctx: Context<ConfigContext>, paused: bool) -> Result<()> {
-    require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Pauser), ParamError::AdminUnauthorized);
+    let old_paused = ctx.accounts.config.paused;
     ctx.accounts.config.paused = paused;
+    emit!(PausedChanged {
+        old_paused,
+        new_paused: paused,
+        authority: ctx.accounts.authority.key(),
+    });
     Ok(())
 }
 
-pub fn set_fee_bp_ix(ctx: Context<ConfigContext>, fee_bp: u64) -> Result<()> {
-    require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
-    require!(fee_bp <= 100, ParamError::FeeTooHigh);
-    ctx.accounts.config.fee_bp = fee_bp;
+pub fn set_lane_paused_ix(ctx: Context<ConfigContext>, lane: Lane, paused: bool) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Pauser), ParamError::AdminUnauthorized);
+    let old_paused = ctx.accounts.config.lane_paused(lane);
+    ctx.accounts.config.set_lane_paused(lane, paused);
+    emit!(LanePausedChanged {
+        lane,
+        old_paused,
+        new_paused: paused,
+        authority: ctx.accounts.authority.key(),
+    });
     Ok(())
 }
 
-pub fn set_signer_key_ix(ctx: Context<ConfigContext>, signer_key: [u8; 32]) -> Result<()> {
-    require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
-    ctx.accounts.config.signer_key = signer_key;
+/// Caps USDC gas drops towards every domain, on top of the domain's own limit.
+pub fn set_max_usdc_gas_drop_ix(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    let old_limit = ctx.accounts.config.max_usdc_gas_drop;
+    ctx.accounts.config.max_usdc_gas_drop = max_gas;
+    emit!(GasDropLimitChanged {
+        limit: GasDropLimit::Usdc,
+        old_limit,
+        new_limit: max_gas,
+        authority: ctx.accounts.authority.key(),
+    });
     Ok(())
 }
 
-pub fn set_fee_collector_ix(ctx: Context<ConfigContext>, fee_collector_sol: Pubkey, fee_collector_usdc: Pubkey) -> Result<()> {
-    require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
-    ctx.accounts.config.fee_collector_sol = fee_collector_sol;
-    ctx.accounts.config.fee_collector_usdc = fee_collector_usdc;
+/// Caps native gas drops towards every domain, on top of the domain's own limit.
+pub fn set_max_native_gas_drop_ix(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    let old_limit = ctx.accounts.config.max_native_gas_drop;
+    ctx.accounts.config.max_native_gas_drop = max_gas;
+    emit!(GasDropLimitChanged {
+        limit: GasDropLimit::Native,
+        old_limit,
+        new_limit: max_gas,
+        authority: ctx.accounts.authority.key(),
+    });
     Ok(())
 }
 
-pub fn set_gas_drop_collector_ix(ctx: Context<ConfigContext>, gas_drop_collector_sol: Pubkey, gas_drop_collector_usdc: Pubkey) -> Result<()> {
+pub fn set_max_receive_gas_drop_ix(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    let old_limit = ctx.accounts.config.max_receive_gas_drop;
+    ctx.accounts.config.max_receive_gas_drop = max_gas;
+    emit!(GasDropLimitChanged {
+        limit: GasDropLimit::Receive,
+        old_limit,
+        new_limit: max_gas,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+/// Bounds `usdc_amount` of every transfer, a zero maximum means no cap.
+pub fn set_transfer_amount_limits_ix(ctx: Context<ConfigContext>, min_transfer_amount: u64, max_transfer_amount: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    require!(
+        max_transfer_amount == 0 || min_transfer_amount <= max_transfer_amount,
+        ParamError::InvalidAmountLimits,
+    );
+    let config = &mut ctx.accounts.config;
+    emit!(TransferAmountLimitsChanged {
+        old_min_transfer_amount: config.min_transfer_amount,
+        new_min_transfer_amount: min_transfer_amount,
+        old_max_transfer_amount: config.max_transfer_amount,
+        new_max_transfer_amount: max_transfer_amount,
+        authority: ctx.accounts.authority.key(),
+    });
+    config.min_transfer_amount = min_transfer_amount;
+    config.max_transfer_amount = max_transfer_amount;
+    Ok(())
+}
+
+/// Prices native gas drops with `oracle`: the signed gas drop amount is then in micro-USDC and
+/// converted to lamports on-chain. The default key turns the oracle off. Quotes commit to the
+/// pricing mode, those signed before turning the oracle on or off are rejected.
+pub fn set_gas_drop_oracle_ix(ctx: Context<ConfigContext>, oracle: Pubkey, max_price_age: u64, max_confidence_bp: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    let config = &mut ctx.accounts.config;
+    emit!(GasDropOracleChanged {
+        old_oracle: config.gas_drop_oracle,
+        new_oracle: oracle,
+        max_price_age,
+        max_confidence_bp,
+        authority: ctx.accounts.authority.key(),
+    });
+    config.gas_drop_oracle = oracle;
+    config.max_oracle_price_age = max_price_age;
+    config.max_oracle_confidence_bp = max_confidence_bp;
+    Ok(())
+}
+
+/// Rejects legacy quotes from now on. Accepting them again goes through the timelock with
+/// `ConfigChange::RequireFullQuote`.
+pub fn set_require_full_quote_ix(ctx: Context<ConfigContext>, require_full_quote: bool) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::SignerManager), ParamError::AdminUnauthorized);
+    require!(require_full_quote, ParamError::TimelockRequired);
+    let old_require_full_quote = ctx.accounts.config.require_full_quote;
+    ctx.accounts.config.require_full_quote = require_full_quote;
+    emit!(RequireFullQuoteChanged {
+        old_require_full_quote,
+        new_require_full_quote: require_full_quote,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+pub fn set_allowlist_only_ix(ctx: Context<ConfigContext>, allowlist_only: bool) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Compliance), ParamError::AdminUnauthorized);
+    let old_allowlist_only = ctx.accounts.config.allowlist_only;
+    ctx.accounts.config.allowlist_only = allowlist_only;
+    emit!(AllowlistOnlyChanged {
+        old_allowlist_only,
+        new_allowlist_only: allowlist_only,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+pub fn grant_role_ix(ctx: Context<RoleContext>, role: Role, holder: Pubkey) -> Result<()> {
     require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
-    ctx.accounts.config.gas_drop_collector_sol = gas_drop_collector_sol;
-    ctx.accounts.config.gas_drop_collector_usdc = gas_drop_collector_usdc;
+    let old_holder = ctx.accounts.config.role_holder(role);
+    ctx.accounts.config.set_role_holder(role, holder);
+    emit!(RoleChanged {
+        role,
+        old_holder,
+        new_holder: holder,
+        authority: ctx.accounts.owner.key(),
+    });
     Ok(())
 }
 
-pub fn set_max_usdc_gas_drop_ix(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+pub fn revoke_role_ix(ctx: Context<RoleContext>, role: Role) -> Result<()> {
     require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
-    ctx.accounts.config.max_usdc_gas_drop = max_gas;
+    let old_holder = ctx.accounts.config.role_holder(role);
+    ctx.accounts.config.set_role_holder(role, Pubkey::default());
+    emit!(RoleChanged {
+        role,
+        old_holder,
+        new_holder: Pubkey::default(),
+        authority: ctx.accounts.owner.key(),
+    });
     Ok(())
 }
 
-pub fn set_max_native_gas_drop_ix(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+pub fn propose_owner_ix(ctx: Context<TransferOwnershipContext>, new_owner: Pubkey) -> Result<()> {
     require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
-    ctx.accounts.config.max_native_gas_drop = max_gas;
+    require!(new_owner != Pubkey::default(), ParamError::NoPendingOwner);
+    ctx.accounts.config.pending_owner = new_owner;
+    emit!(OwnershipTransferProposed {
+        owner: ctx.accounts.owner.key(),
+        pending_owner: new_owner,
+    });
     Ok(())
 }
 
-pub fn transfer_ownership_ix(ctx: Context<TransferOwnershipContext>, new_owner: Pubkey) -> Result<()> {
+pub fn cancel_ownership_proposal_ix(ctx: Context<TransferOwnershipContext>) -> Result<()> {
     require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
-    ctx.accounts.config.owner = new_owner;
+    let pending_owner = ctx.accounts.config.pending_owner;
+    require!(pending_owner != Pubkey::default(), ParamError::NoPendingOwner);
+    ctx.accounts.config.pending_owner = Pubkey::default();
+    emit!(OwnershipProposalCancelled {
+        owner: ctx.accounts.owner.key(),
+        pending_owner,
+    });
+    Ok(())
+}
+
+pub fn accept_ownership_ix(ctx: Context<AcceptOwnershipContext>) -> Result<()> {
+    let pending_owner = ctx.accounts.config.pending_owner;
+    require!(pending_owner != Pubkey::default(), ParamError::NoPendingOwner);
+    require!(ctx.accounts.new_owner.key() == pending_owner, ParamError::NotPendingOwner);
+    let previous_owner = ctx.accounts.config.owner;
+    ctx.accounts.config.owner = pending_owner;
+    ctx.accounts.config.pending_owner = Pubkey::default();
+    emit!(OwnershipTransferred {
+        previous_owner,
+        new_owner: pending_owner,
+    });
+    Ok(())
+}
+
+pub fn withdraw_gas_vault_ix(ctx: Context<WithdrawGasVaultContext>, amount: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Treasury), ParamError::AdminUnauthorized);
+    let gas_vault_seeds: &[&[&[u8]]] = &[&[GasVault::SEED_PREFIX, &[ctx.bumps.gas_vault]]];
+    system_program::transfer(CpiContext::new_with_signer(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.gas_vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+        },
+        gas_vault_seeds,
+    ), amount)?;
+    emit!(GasVaultWithdrawn {
+        destination: ctx.accounts.destination.key(),
+        amount,
+        authority: ctx.accounts.authority.key(),
+    });
     Ok(())
 }
 
@@ -58,8 +239,9 @@ pub struct ConfigContext<'info> {
     #[account(mut, seeds=[b"config"], bump)]
     pub config: Account<'info, Config>,
 
+    // Owner or holder of the role required by the instruction
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
@@ -72,3 +254,35 @@ pub struct TransferOwnershipContext<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 }
+
+#[derive(Accounts)]
+pub struct RoleContext<'info> {
+    #[account(mut, seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwnershipContext<'info> {
+    #[account(mut, seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    pub new_owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawGasVaultContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [GasVault::SEED_PREFIX], bump)]
+    pub gas_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,124 @@
+use std::mem::size_of;
+use anchor_lang::prelude::*;
+use crate::{
+    state::{
+        Config,
+        DomainConfig,
+        DomainConfigParams,
+        FeeTier,
+        RateLimitBucket,
+        Role,
+    },
+    errors::ParamError,
+    events::{
+        DomainConfigCreated,
+        DomainConfigChanged,
+        DomainEnabledChanged,
+    },
+    cctp::LOCAL_DOMAIN,
+};
+
+/// Creates a disabled domain charging `Config::default_fee_bp`, seeded with the global gas drop
+/// caps. Its fees are changed through the timelock with `ConfigChange::DomainFees`.
+pub fn create_domain_config_ix(ctx: Context<CreateDomainConfigContext>, domain: u32) -> Result<()> {
+    require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
+    require!(domain < 32 && domain != LOCAL_DOMAIN, ParamError::InvalidDomain);
+    let config = &ctx.accounts.config;
+    ctx.accounts.domain_config.set_inner(DomainConfig {
+        domain,
+        enabled: false,
+        fee_bp: config.default_fee_bp,
+        min_fee: 0,
+        fee_tiers: [FeeTier::default(); DomainConfig::MAX_FEE_TIERS],
+        fee_tier_count: 0,
+        min_percentage_fee: 0,
+        max_percentage_fee: 0,
+        rate_limit: RateLimitBucket::default(),
+        max_usdc_gas_drop: config.max_usdc_gas_drop,
+        max_native_gas_drop: config.max_native_gas_drop,
+        cctp_versions: DomainConfig::ALL_CCTP_VERSIONS,
+        destination_caller: [0; 32],
+        bump: ctx.bumps.domain_config,
+    });
+    emit!(DomainConfigCreated {
+        domain,
+        params: ctx.accounts.domain_config.params(),
+        authority: ctx.accounts.owner.key(),
+    });
+    Ok(())
+}
+
+pub fn update_domain_config_ix(ctx: Context<DomainConfigContext>, _domain: u32, params: DomainConfigParams) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    require!(
+        params.cctp_versions & !DomainConfig::ALL_CCTP_VERSIONS == 0,
+        ParamError::InvalidCctpVersions,
+    );
+    let domain_config = &mut ctx.accounts.domain_config;
+    let old_params = domain_config.params();
+    if params.rate_limit_capacity != domain_config.rate_limit.capacity {
+        domain_config.rate_limit.set_capacity(params.rate_limit_capacity, Clock::get()?.unix_timestamp);
+    }
+    domain_config.max_usdc_gas_drop = params.max_usdc_gas_drop;
+    domain_config.max_native_gas_drop = params.max_native_gas_drop;
+    domain_config.cctp_versions = params.cctp_versions;
+    domain_config.destination_caller = params.destination_caller;
+    emit!(DomainConfigChanged {
+        domain: domain_config.domain,
+        old_params,
+        new_params: params,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+pub fn set_domain_enabled_ix(ctx: Context<DomainConfigContext>, _domain: u32, enabled: bool) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Pauser), ParamError::AdminUnauthorized);
+    let old_enabled = ctx.accounts.domain_config.enabled;
+    ctx.accounts.domain_config.enabled = enabled;
+    emit!(DomainEnabledChanged {
+        domain: ctx.accounts.domain_config.domain,
+        old_enabled,
+        new_enabled: enabled,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(domain: u32)]
+pub struct CreateDomainConfigContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = size_of::<DomainConfig>() + 8,
+        seeds = [DomainConfig::SEED_PREFIX, &domain.to_le_bytes()],
+        bump,
+    )]
+    pub domain_config: Account<'info, DomainConfig>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(domain: u32)]
+pub struct DomainConfigContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [DomainConfig::SEED_PREFIX, &domain.to_le_bytes()],
+        bump = domain_config.bump,
+    )]
+    pub domain_config: Account<'info, DomainConfig>,
+
+    // Owner or holder of the role required by the instruction
+    pub authority: Signer<'info>,
+}
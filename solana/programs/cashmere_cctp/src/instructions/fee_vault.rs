@@ -0,0 +1,179 @@
+use anchor_lang::{
+    prelude::*,
+    system_program,
+};
+use anchor_spl::token_interface::{
+    self,
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+};
+use crate::{
+    state::{
+        Config,
+        Custodian,
+        FeeVault,
+        Role,
+    },
+    errors::{ParamError, TransferError},
+    events::FeeWithdrawn,
+    cctp::USDC_MINT,
+};
+
+/// Creates the USDC fee vault and funds the native fee vault with its rent-exempt minimum, so the
+/// first native fee does not have to cover it.
+pub fn initialize_fee_vault_ix(ctx: Context<InitializeFeeVaultContext>) -> Result<()> {
+    require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let missing = rent_exempt_minimum.saturating_sub(ctx.accounts.fee_vault.lamports());
+    if missing > 0 {
+        system_program::transfer(CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+            },
+        ), missing)?;
+    }
+    Ok(())
+}
+
+pub fn withdraw_fee_ix(ctx: Context<WithdrawFeeContext>, usdc_amount: u64, native_amount: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Treasury), ParamError::AdminUnauthorized);
+
+    if usdc_amount > 0 {
+        require!(usdc_amount <= ctx.accounts.fee_vault_token_account.amount, ParamError::InsufficientVaultBalance);
+        let custodian_seeds: &[&[&[u8]]] = &[&[Custodian::SEED_PREFIX, &[ctx.accounts.custodian.bump]]];
+        token_interface::transfer_checked(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.fee_vault_token_account.to_account_info(),
+                mint: ctx.accounts.usdc_mint.to_account_info(),
+                to: ctx.accounts.usdc_destination.to_account_info(),
+                authority: ctx.accounts.custodian.to_account_info(),
+            },
+            custodian_seeds,
+        ), usdc_amount, ctx.accounts.usdc_mint.decimals)?;
+    }
+
+    if native_amount > 0 {
+        // the vault keeps its rent-exempt minimum
+        let available = ctx.accounts.fee_vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0));
+        require!(native_amount <= available, ParamError::InsufficientVaultBalance);
+        let fee_vault_seeds: &[&[&[u8]]] = &[&[FeeVault::SEED_PREFIX, &[ctx.bumps.fee_vault]]];
+        system_program::transfer(CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.native_destination.to_account_info(),
+            },
+            fee_vault_seeds,
+        ), native_amount)?;
+    }
+
+    emit!(FeeWithdrawn {
+        usdc_amount,
+        native_amount,
+        usdc_destination: ctx.accounts.usdc_destination.key(),
+        native_destination: ctx.accounts.native_destination.key(),
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeVaultContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Seeds must be \["fee_vault"\].
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED_PREFIX],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    /// Seeds must be \["fee_vault_token"\].
+    #[account(
+        init,
+        payer = owner,
+        token::mint = usdc_mint,
+        token::authority = custodian,
+        token::token_program = token_program,
+        seeds = [FeeVault::TOKEN_SEED_PREFIX],
+        bump,
+    )]
+    pub fee_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Seeds must be \["emitter"\].
+    #[account(
+        seeds = [Custodian::SEED_PREFIX],
+        bump = custodian.bump,
+    )]
+    pub custodian: Account<'info, Custodian>,
+
+    #[account(
+        address = USDC_MINT,
+        constraint = usdc_mint.to_account_info().owner == token_program.key @ TransferError::InvalidTokenProgram,
+    )]
+    pub usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFeeContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    // Owner or treasury
+    pub authority: Signer<'info>,
+
+    /// Seeds must be \["fee_vault"\].
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED_PREFIX],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    /// Seeds must be \["fee_vault_token"\].
+    #[account(
+        mut,
+        seeds = [FeeVault::TOKEN_SEED_PREFIX],
+        bump,
+    )]
+    pub fee_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::token_program = token_program,
+    )]
+    pub usdc_destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub native_destination: SystemAccount<'info>,
+
+    /// Seeds must be \["emitter"\].
+    #[account(
+        seeds = [Custodian::SEED_PREFIX],
+        bump = custodian.bump,
+    )]
+    pub custodian: Account<'info, Custodian>,
+
+    #[account(
+        address = USDC_MINT,
+        constraint = usdc_mint.to_account_info().owner == token_program.key @ TransferError::InvalidTokenProgram,
+    )]
+    pub usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
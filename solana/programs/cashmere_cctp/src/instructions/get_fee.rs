@@ -1,12 +1,27 @@
 use anchor_lang::prelude::*;
 use crate::{
-    state::Config,
-    utils::calculate_fee,
+    state::{Config, DomainConfig},
+    utils::FeeBreakdown,
 };
+use super::charged_gas_drop;
 
 
-pub fn get_fee_ix(ctx: Context<GetFeeContext>, fee: u64, amount: u64) -> Result<u64> {
-    Ok(calculate_fee(amount, ctx.accounts.config.fee_bp, fee))
+pub fn get_fee_ix(
+    ctx: Context<GetFeeContext>,
+    _destination_domain: u32,
+    fee: u64,
+    amount: u64,
+    gas_drop_amount: u64,
+    fee_is_native: bool,
+) -> Result<FeeBreakdown> {
+    let gas_drop = charged_gas_drop(
+        &ctx.accounts.config,
+        ctx.accounts.price_oracle.as_deref(),
+        gas_drop_amount,
+        fee_is_native,
+        Clock::get()?.unix_timestamp,
+    )?;
+    Ok(FeeBreakdown::new(&ctx.accounts.domain_config, amount, fee, gas_drop, fee_is_native))
 }
 
 #[derive(Accounts)]
@@ -14,5 +29,13 @@ pub fn get_fee_ix(ctx: Context<GetFeeContext>, fee: u64, amount: u64) -> Result<
 pub struct GetFeeContext<'info> {
     #[account(seeds=[b"config"], bump)]
     pub config: Account<'info, Config>,
-}
 
+    #[account(
+        seeds = [DomainConfig::SEED_PREFIX, &destination_domain.to_le_bytes()],
+        bump = domain_config.bump,
+    )]
+    pub domain_config: Account<'info, DomainConfig>,
+
+    /// CHECK: Must equal `config.gas_drop_oracle`, required for native gas drops while it is set.
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+}
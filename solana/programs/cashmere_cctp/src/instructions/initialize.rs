@@ -10,25 +10,45 @@ use crate::{
 
 pub fn initialize_ix(
     ctx: Context<InitializeContext>,
-    fee_collector_sol: Pubkey,
-    fee_collector_usdc: Pubkey,
     gas_drop_collector_sol: Pubkey,
     gas_drop_collector_usdc: Pubkey,
 ) -> Result<()> {
     ctx.accounts.config.paused = false;
+    ctx.accounts.config.paused_lanes = 0;
     ctx.accounts.config.owner = ctx.accounts.owner.key();
-    ctx.accounts.config.fee_collector_sol = fee_collector_sol;
-    ctx.accounts.config.fee_collector_usdc = fee_collector_usdc;
+    ctx.accounts.config.pending_owner = Pubkey::default();
+    ctx.accounts.config.pauser = Pubkey::default();
+    ctx.accounts.config.fee_manager = Pubkey::default();
+    ctx.accounts.config.signer_manager = Pubkey::default();
+    ctx.accounts.config.treasury = Pubkey::default();
+    ctx.accounts.config.compliance = Pubkey::default();
     ctx.accounts.config.gas_drop_collector_sol = gas_drop_collector_sol;
     ctx.accounts.config.gas_drop_collector_usdc = gas_drop_collector_usdc;
-    ctx.accounts.config.fee_bp = 0;
     ctx.accounts.config.nonce = 0;
     ctx.accounts.config.max_usdc_gas_drop = 100_000_000;
     ctx.accounts.config.max_native_gas_drop = 0;
+    ctx.accounts.config.max_receive_gas_drop = 0;
+    ctx.accounts.config.min_transfer_amount = 0;
+    ctx.accounts.config.max_transfer_amount = 0;
+    ctx.accounts.config.gas_drop_oracle = Pubkey::default();
+    ctx.accounts.config.max_oracle_price_age = 0;
+    ctx.accounts.config.max_oracle_confidence_bp = 0;
     ctx.accounts.custodian.set_inner(Custodian {
         bump: ctx.bumps.custodian,
     });
     ctx.accounts.config.signer_key = [0; 32];
+    ctx.accounts.config.previous_signer_key = [0; 32];
+    ctx.accounts.config.previous_signer_key_expiry = 0;
+    ctx.accounts.config.signer_set = [[0; 32]; Config::MAX_SIGNERS];
+    ctx.accounts.config.signer_set_len = 0;
+    ctx.accounts.config.signer_threshold = 0;
+    ctx.accounts.config.require_full_quote = true;
+    ctx.accounts.config.allowlist_only = false;
+    ctx.accounts.config.timelock_delay = Config::DEFAULT_TIMELOCK_DELAY;
+    ctx.accounts.config.next_change_id = 0;
+    ctx.accounts.config.default_fee_bp = 0;
+    ctx.accounts.config.version = Config::VERSION;
+    ctx.accounts.config.reserved = [0; Config::RESERVED_LEN];
     Ok(())
 }
 
@@ -36,7 +56,7 @@ pub fn initialize_ix(
 pub struct InitializeContext<'info> {
     #[account(init,
               payer = owner,
-              space = size_of::<Config>() + 8,
+              space = Config::INIT_SPACE + 8,
               seeds = [b"config"],
               bump)]
     pub config: Account<'info, Config>,
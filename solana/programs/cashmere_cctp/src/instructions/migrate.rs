@@ -0,0 +1,158 @@
+use anchor_lang::{
+    prelude::*,
+    system_program,
+};
+use crate::{
+    state::{Config, ConfigV0},
+    errors::ParamError,
+    events::{ConfigMigrated, LegacyFeeCollectorsDropped},
+};
+
+/// Brings a config account with the [ConfigV0] layout to `Config::VERSION`: the account is read
+/// through the frozen layout, grown and rewritten field by field, see `ConfigV0::migrate`. Later
+/// layouts keep `version` first and take their fields out of `reserved`.
+pub fn migrate_config_ix(ctx: Context<MigrateConfigContext>) -> Result<()> {
+    let config = ctx.accounts.config.to_account_info();
+    let legacy = read_legacy_config(&config.try_borrow_data()?)?;
+    require!(legacy.owner == ctx.accounts.owner.key(), ParamError::AdminUnauthorized);
+    let migrated = Box::new(legacy.migrate());
+
+    let new_len = Config::INIT_SPACE + 8;
+    let rent = Rent::get()?.minimum_balance(new_len).saturating_sub(config.lamports());
+    if rent > 0 {
+        system_program::transfer(CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: config.clone(),
+            },
+        ), rent)?;
+    }
+    config.realloc(new_len, true)?;
+    migrated.try_serialize(&mut &mut config.try_borrow_mut_data()?[..])?;
+
+    emit!(ConfigMigrated {
+        old_version: 0,
+        new_version: Config::VERSION,
+        authority: ctx.accounts.owner.key(),
+    });
+    emit!(LegacyFeeCollectorsDropped {
+        fee_collector_sol: legacy.fee_collector_sol,
+        fee_collector_usdc: legacy.fee_collector_usdc,
+    });
+    Ok(())
+}
+
+/// Reads a config account that still has the [ConfigV0] layout.
+pub fn read_legacy_config(data: &[u8]) -> Result<ConfigV0> {
+    require!(data.len() >= 8 && data[..8] == *Config::DISCRIMINATOR, ParamError::InvalidConfigAccount);
+    require!(data.len() == ConfigV0::ACCOUNT_LEN, ParamError::ConfigAlreadyMigrated);
+    Ok(ConfigV0::deserialize(&mut &data[8..])?)
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfigContext<'info> {
+    /// CHECK: Mutable. Seeds must be \["config"\]. Read manually, it still has the legacy layout.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        owner = crate::ID,
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    // Owner, pays the rent of the grown account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+    use super::*;
+
+    // baseline `Config` account: discriminator, then the fields in declaration order
+    fn baseline_account() -> Vec<u8> {
+        let mut data = Config::DISCRIMINATOR.to_vec();
+        for key in 1..=5u8 {
+            data.extend_from_slice(&[key; 32]); // owner, fee collectors, gas drop collectors
+        }
+        data.extend_from_slice(&25u64.to_le_bytes()); // fee_bp
+        data.extend_from_slice(&1234u64.to_le_bytes()); // nonce
+        data.extend_from_slice(&[9; 32]); // signer_key
+        data.extend_from_slice(&50_000_000u64.to_le_bytes()); // max_usdc_gas_drop
+        data.extend_from_slice(&7_000_000u64.to_le_bytes()); // max_native_gas_drop
+        data.push(1); // paused
+        data.resize(size_of::<ConfigV0>() + 8, 0);
+        data
+    }
+
+    #[test]
+    fn migrates_baseline_layout() {
+        let data = baseline_account();
+        assert_eq!(data.len(), ConfigV0::ACCOUNT_LEN);
+
+        let legacy = read_legacy_config(&data).unwrap();
+        assert_eq!(legacy.fee_collector_sol, Pubkey::new_from_array([2; 32]));
+        assert_eq!(legacy.fee_collector_usdc, Pubkey::new_from_array([3; 32]));
+
+        let mut migrated_data = vec![0; Config::INIT_SPACE + 8];
+        legacy.migrate().try_serialize(&mut &mut migrated_data[..]).unwrap();
+        let config = Config::try_deserialize(&mut &migrated_data[..]).unwrap();
+
+        // carried over
+        assert_eq!(config.owner, Pubkey::new_from_array([1; 32]));
+        assert_eq!(config.gas_drop_collector_sol, Pubkey::new_from_array([4; 32]));
+        assert_eq!(config.gas_drop_collector_usdc, Pubkey::new_from_array([5; 32]));
+        assert_eq!(config.nonce, 1234);
+        assert_eq!(config.signer_key, [9; 32]);
+        assert_eq!(config.max_usdc_gas_drop, 50_000_000);
+        assert_eq!(config.max_native_gas_drop, 7_000_000);
+        assert!(config.paused);
+        assert_eq!(config.default_fee_bp, 25);
+
+        // new fields
+        assert_eq!(config.version, Config::VERSION);
+        assert_eq!(config.pending_owner, Pubkey::default());
+        assert_eq!(config.pauser, Pubkey::default());
+        assert_eq!(config.fee_manager, Pubkey::default());
+        assert_eq!(config.signer_manager, Pubkey::default());
+        assert_eq!(config.treasury, Pubkey::default());
+        assert_eq!(config.compliance, Pubkey::default());
+        assert_eq!(config.previous_signer_key, [0; 32]);
+        assert_eq!(config.previous_signer_key_expiry, 0);
+        assert_eq!(config.signer_set(), &[] as &[[u8; 32]]);
+        assert_eq!(config.signer_threshold, 0);
+        assert_eq!(config.max_receive_gas_drop, 0);
+        assert_eq!(config.min_transfer_amount, 0);
+        assert_eq!(config.max_transfer_amount, 0);
+        assert_eq!(config.gas_drop_oracle, Pubkey::default());
+        assert_eq!(config.max_oracle_price_age, 0);
+        assert_eq!(config.max_oracle_confidence_bp, 0);
+        assert_eq!(config.paused_lanes, 0);
+        assert!(!config.require_full_quote);
+        assert!(!config.allowlist_only);
+        assert_eq!(config.timelock_delay, Config::DEFAULT_TIMELOCK_DELAY);
+        assert_eq!(config.next_change_id, 0);
+        assert_eq!(config.reserved, [0; Config::RESERVED_LEN]);
+    }
+
+    #[test]
+    fn rejects_migrated_config() {
+        let mut data = vec![0; Config::INIT_SPACE + 8];
+        ConfigV0::deserialize(&mut &baseline_account()[8..]).unwrap()
+            .migrate()
+            .try_serialize(&mut &mut data[..])
+            .unwrap();
+        assert_eq!(read_legacy_config(&data).unwrap_err(), ParamError::ConfigAlreadyMigrated.into());
+    }
+
+    #[test]
+    fn rejects_other_accounts() {
+        let mut data = baseline_account();
+        data[0] ^= 1;
+        assert_eq!(read_legacy_config(&data).unwrap_err(), ParamError::InvalidConfigAccount.into());
+    }
+}
@@ -1,9 +1,29 @@
 pub mod initialize;
 pub mod admin;
+pub mod domain;
 pub mod get_fee;
+pub mod quote;
 pub mod transfer;
+pub mod receive;
+pub mod used_quote;
+pub mod fee_vault;
+pub mod partner;
+pub mod rate_limit;
+pub mod sender_list;
+pub mod timelock;
+pub mod migrate;
 
 pub use initialize::*;
 pub use admin::*;
+pub use domain::*;
 pub use get_fee::*;
+pub use quote::*;
 pub use transfer::*;
+pub use receive::*;
+pub use used_quote::*;
+pub use fee_vault::*;
+pub use partner::*;
+pub use rate_limit::*;
+pub use sender_list::*;
+pub use timelock::*;
+pub use migrate::*;
@@ -0,0 +1,87 @@
+use std::mem::size_of;
+use anchor_lang::prelude::*;
+use crate::{
+    state::{
+        Config,
+        Partner,
+        Role,
+    },
+    errors::ParamError,
+    events::PartnerChanged,
+};
+
+/// Registers a partner without a fee share, its share and payout account are set through the
+/// timelock with `ConfigChange::Partner`.
+pub fn register_partner_ix(ctx: Context<RegisterPartnerContext>, partner_key: Pubkey) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    ctx.accounts.partner.set_inner(Partner {
+        partner: partner_key,
+        share_bp: 0,
+        payout: Pubkey::default(),
+        bump: ctx.bumps.partner,
+    });
+    emit!(PartnerChanged {
+        partner: partner_key,
+        old_share_bp: 0,
+        new_share_bp: 0,
+        old_payout: Pubkey::default(),
+        new_payout: Pubkey::default(),
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+/// Removes a partner from the registry, quotes naming it can no longer be used.
+pub fn remove_partner_ix(ctx: Context<RemovePartnerContext>, partner_key: Pubkey) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    emit!(PartnerChanged {
+        partner: partner_key,
+        old_share_bp: ctx.accounts.partner.share_bp,
+        new_share_bp: 0,
+        old_payout: ctx.accounts.partner.payout,
+        new_payout: Pubkey::default(),
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(partner_key: Pubkey)]
+pub struct RegisterPartnerContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = size_of::<Partner>() + 8,
+        seeds = [Partner::SEED_PREFIX, partner_key.as_ref()],
+        bump,
+    )]
+    pub partner: Account<'info, Partner>,
+
+    // Owner or fee manager
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(partner_key: Pubkey)]
+pub struct RemovePartnerContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [Partner::SEED_PREFIX, partner_key.as_ref()],
+        bump = partner.bump,
+    )]
+    pub partner: Account<'info, Partner>,
+
+    // Owner or fee manager, receives the rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
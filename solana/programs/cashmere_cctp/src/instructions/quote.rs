@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use crate::{
+    state::{AllowedSender, BlockedSender, Config, DomainConfig, RateLimit},
+    utils::FeeBreakdown,
+};
+use super::{
+    charged_gas_drop,
+    rate_limit_violation,
+    sender_violation,
+    transfer_violation,
+    QuoteViolation,
+};
+
+/// Outcome of a transfer with the given parameters, as computed by `quote`. Rent of the temporary
+/// transfer accounts is not included in `lamport_debit`.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct QuoteResult {
+    pub usdc_debit: u64, // taken from the sender token account, gas drop included
+    pub lamport_debit: u64, // native fee and gas drop
+    pub burn_amount: u64,
+    pub fees: FeeBreakdown,
+    pub violation: Option<QuoteViolation>,
+}
+
+/// Simulates the fee collection of a transfer without the signed quote, for preflight with
+/// `simulateTransaction`.
+pub fn quote_ix(
+    ctx: Context<QuoteContext>,
+    usdc_amount: u64,
+    _destination_domain: u32,
+    cctp_version: u8,
+    fee: u64,
+    gas_drop_amount: u64,
+    fee_is_native: bool,
+) -> Result<QuoteResult> {
+    let config = &ctx.accounts.config;
+    let domain_config = &ctx.accounts.domain_config;
+    let now = Clock::get()?.unix_timestamp;
+    let gas_drop = charged_gas_drop(
+        config,
+        ctx.accounts.price_oracle.as_deref(),
+        gas_drop_amount,
+        fee_is_native,
+        now,
+    )?;
+    let fees = FeeBreakdown::new(domain_config, usdc_amount, fee, gas_drop, fee_is_native);
+    // dry run of the outflow limits on copies of the buckets
+    let mut global_rate_limit = ctx.accounts.rate_limit.bucket;
+    let mut domain_rate_limit = domain_config.rate_limit;
+    let violation = sender_violation(config, &ctx.accounts.blocked_sender, &ctx.accounts.allowed_sender)
+        .or_else(|| transfer_violation(config, domain_config, cctp_version, usdc_amount, &fees, fee_is_native))
+        .or_else(|| rate_limit_violation(&mut global_rate_limit, &mut domain_rate_limit, fees.net_amount, now));
+
+    let (usdc_debit, lamport_debit) = if fee_is_native {
+        (usdc_amount, fee.saturating_add(gas_drop))
+    } else {
+        (usdc_amount.saturating_add(gas_drop), 0)
+    };
+
+    Ok(QuoteResult {
+        usdc_debit,
+        lamport_debit,
+        burn_amount: fees.net_amount,
+        fees,
+        violation,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(usdc_amount: u64, destination_domain: u32)]
+pub struct QuoteContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [DomainConfig::SEED_PREFIX, &destination_domain.to_le_bytes()],
+        bump = domain_config.bump,
+    )]
+    pub domain_config: Account<'info, DomainConfig>,
+
+    #[account(
+        seeds = [RateLimit::SEED_PREFIX],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+
+    /// CHECK: Must equal `config.gas_drop_oracle`, required for native gas drops while it is set.
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Sender of the simulated transfer.
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist entry of `owner`.
+    ///
+    /// Seeds must be \["blocked_sender", owner\].
+    #[account(
+        seeds = [BlockedSender::SEED_PREFIX, owner.key().as_ref()],
+        bump,
+    )]
+    pub blocked_sender: UncheckedAccount<'info>,
+
+    /// CHECK: Allowlist entry of `owner`.
+    ///
+    /// Seeds must be \["allowed_sender", owner\].
+    #[account(
+        seeds = [AllowedSender::SEED_PREFIX, owner.key().as_ref()],
+        bump,
+    )]
+    pub allowed_sender: UncheckedAccount<'info>,
+}
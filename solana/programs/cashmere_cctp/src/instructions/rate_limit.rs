@@ -0,0 +1,72 @@
+use std::mem::size_of;
+use anchor_lang::prelude::*;
+use crate::{
+    state::{
+        Config,
+        RateLimit,
+        RateLimitBucket,
+        Role,
+    },
+    errors::ParamError,
+    events::RateLimitChanged,
+};
+
+/// Creates the global outflow limit, disabled until a capacity is set.
+pub fn initialize_rate_limit_ix(ctx: Context<InitializeRateLimitContext>) -> Result<()> {
+    require!(ctx.accounts.owner.key() == ctx.accounts.config.owner, ParamError::AdminUnauthorized);
+    ctx.accounts.rate_limit.set_inner(RateLimit {
+        bucket: RateLimitBucket::default(),
+        bump: ctx.bumps.rate_limit,
+    });
+    Ok(())
+}
+
+/// Sets the USDC that may leave through all domains per 24 hours, 0 disables the limit.
+pub fn set_rate_limit_ix(ctx: Context<RateLimitContext>, capacity: u64) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::FeeManager), ParamError::AdminUnauthorized);
+    let bucket = &mut ctx.accounts.rate_limit.bucket;
+    let old_capacity = bucket.capacity;
+    bucket.set_capacity(capacity, Clock::get()?.unix_timestamp);
+    emit!(RateLimitChanged {
+        old_capacity,
+        new_capacity: capacity,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRateLimitContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = size_of::<RateLimit>() + 8,
+        seeds = [RateLimit::SEED_PREFIX],
+        bump,
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RateLimitContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED_PREFIX],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+
+    // Owner or fee manager
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::{
+    prelude::*,
+    system_program,
+};
+use borsh::{BorshSerialize, to_vec};
+use crate::{
+    state::{Config, GasVault, Lane},
+    instructions::lane_paused_error,
+    errors::TransferError,
+    utils::verify_quote_signature,
+};
+
+/// Version tag of the signed receive payload, see [`ReceiveParams`]. The high bit keeps it apart
+/// from the transfer quote tags and from legacy transfer quotes, which start with the CCTP version.
+pub const RECEIVE_QUOTE_VERSION: u8 = 0x81;
+
+/// Signed gas drop authorization for an inbound transfer. It is bound to a single CCTP message
+/// through `source_domain` and `cctp_nonce`, which CCTP only lets through once.
+#[derive(BorshSerialize)]
+pub struct ReceiveParams {
+    pub version: u8,
+    pub program_id: Pubkey,
+    pub cctp_version: u8,
+    pub source_domain: u32,
+    pub source_nonce: u64,
+    pub cctp_nonce: [u8; 32],
+    pub recipient: Pubkey,
+    pub gas_drop_amount: u64,
+}
+
+pub fn pre_receive<'info>(
+    config: &Config,
+    signature: &AccountInfo<'info>,
+    gas_vault: &AccountInfo<'info>,
+    gas_vault_bump: u8,
+    recipient: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    params: &ReceiveParams,
+    ed25519_ix_index: u16,
+) -> Result<()> {
+    require!(!config.paused, TransferError::Paused);
+    if let Some(lane) = Lane::cctp(params.cctp_version).filter(|lane| config.lane_paused(*lane)) {
+        return Err(lane_paused_error(lane));
+    }
+    if params.gas_drop_amount > 0 && config.lane_paused(Lane::GasDrop) {
+        return Err(lane_paused_error(Lane::GasDrop));
+    }
+    require!(params.gas_drop_amount <= config.max_receive_gas_drop, TransferError::GasDropLimitExceeded);
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    verify_quote_signature(&signature.to_account_info(), ed25519_ix_index, &to_vec(params)?, config, now)?;
+
+    // deliver gas drop in SOL
+    if params.gas_drop_amount > 0 {
+        let gas_vault_seeds: &[&[&[u8]]] = &[&[GasVault::SEED_PREFIX, &[gas_vault_bump]]];
+        system_program::transfer(CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: gas_vault.to_account_info(),
+                to: recipient.to_account_info(),
+            },
+            gas_vault_seeds,
+        ), params.gas_drop_amount)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+mod receive_ix;
+mod receive_v2_ix;
+mod common;
+
+pub use receive_ix::*;
+pub use receive_v2_ix::*;
+pub use common::{
+    pre_receive,
+    ReceiveParams,
+    RECEIVE_QUOTE_VERSION,
+};
@@ -0,0 +1,281 @@
+use anchor_lang::{prelude::*, solana_program::{
+    self,
+    sysvar::instructions as sysvar,
+}};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        Mint,
+        TokenAccount,
+        TokenInterface,
+    },
+};
+use crate::{
+    state::{
+        Custodian,
+        Config,
+        GasVault,
+    },
+    events::ReceiveEvent,
+    errors::{
+        ReceiveError,
+        TransferError,
+    },
+    cctp::{
+        TOKEN_MESSENGER_MINTER_PROGRAM_ID,
+        MESSAGE_TRANSMITTER_PROGRAM_ID,
+        USDC_MINT,
+        message::BurnMessage,
+        message_transmitter_program::{
+            cpi::{
+                ReceiveMessage,
+                ReceiveMessageParams,
+            },
+        },
+    },
+};
+use super::{
+    pre_receive,
+    ReceiveParams,
+    RECEIVE_QUOTE_VERSION,
+};
+
+pub fn receive_ix(
+    ctx: Context<ReceiveContext>,
+    message: Vec<u8>,
+    attestation: Vec<u8>,
+    source_nonce: u64,
+    gas_drop_amount: u64,
+    ed25519_ix_index: u16,
+) -> Result<()> {
+    let burn_message = BurnMessage::parse_v1(&message)?;
+    require_keys_eq!(
+        burn_message.mint_recipient,
+        ctx.accounts.recipient_token_account.key(),
+        ReceiveError::InvalidRecipient,
+    );
+
+    let params = ReceiveParams {
+        version: RECEIVE_QUOTE_VERSION,
+        program_id: crate::ID,
+        cctp_version: 1,
+        source_domain: burn_message.source_domain,
+        source_nonce,
+        cctp_nonce: burn_message.nonce,
+        recipient: ctx.accounts.recipient.key(),
+        gas_drop_amount,
+    };
+    pre_receive(
+        &ctx.accounts.config,
+        &ctx.accounts.signature.to_account_info(),
+        &ctx.accounts.gas_vault.to_account_info(),
+        ctx.bumps.gas_vault,
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &params,
+        ed25519_ix_index,
+    )?;
+    let custodian_seeds: &[&[&[u8]]] = &[&[Custodian::SEED_PREFIX, &[ctx.accounts.custodian.bump]]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.message_transmitter_program.to_account_info(),
+        ReceiveMessage {
+            payer: ctx.accounts.payer.to_account_info(),
+            caller: ctx.accounts.custodian.to_account_info(),
+            authority_pda: ctx.accounts.message_transmitter_authority.to_account_info(),
+            message_transmitter: ctx.accounts.message_transmitter.to_account_info(),
+            used_nonces: ctx.accounts.used_nonces.to_account_info(),
+            receiver: ctx.accounts.token_messenger_minter_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            event_authority: ctx.accounts.message_transmitter_event_authority.to_account_info(),
+            message_transmitter_program: ctx.accounts.message_transmitter_program.to_account_info(),
+            token_messenger: ctx.accounts.token_messenger.to_account_info(),
+            remote_token_messenger: ctx.accounts.remote_token_messenger.to_account_info(),
+            token_minter: ctx.accounts.token_minter.to_account_info(),
+            local_token: ctx.accounts.local_token.to_account_info(),
+            token_pair: ctx.accounts.token_pair.to_account_info(),
+            recipient_token_account: ctx.accounts.recipient_token_account.to_account_info(),
+            custody_token_account: ctx.accounts.custody_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_messenger_event_authority: ctx.accounts.token_messenger_minter_event_authority.to_account_info(),
+        },
+        custodian_seeds,
+    );
+
+    let args = ReceiveMessageParams {
+        message,
+        attestation,
+    };
+
+    const ANCHOR_IX_SELECTOR: [u8; 8] = [38, 144, 127, 225, 31, 225, 238, 25];
+
+    solana_program::program::invoke_signed(
+        &solana_program::instruction::Instruction {
+            program_id: MESSAGE_TRANSMITTER_PROGRAM_ID,
+            accounts: cpi_ctx.to_account_metas(None),
+            data: (ANCHOR_IX_SELECTOR, args).try_to_vec()?,
+        },
+        &cpi_ctx.to_account_infos(),
+        cpi_ctx.signer_seeds,
+    )?;
+
+    emit!(ReceiveEvent {
+        source_domain: burn_message.source_domain,
+        source_nonce,
+        recipient: ctx.accounts.recipient.key(),
+        amount: burn_message.amount,
+        gas_drop_amount,
+        cctp_nonce: burn_message.nonce,
+        cctp_version: 1,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReceiveContext<'info> {
+    // Cashmere CCTP config
+    #[account(seeds=[b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    // Relayer, pays for the CCTP nonce account and the recipient token account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Recipient wallet, receives the gas drop
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    // Recipient ATA, must be the mint recipient of the CCTP message
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Gas drop source.
+    ///
+    /// Seeds must be \["gas_vault"\].
+    #[account(
+        mut,
+        seeds = [GasVault::SEED_PREFIX],
+        bump,
+    )]
+    pub gas_vault: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// This program's emitter authority, used as the CCTP destination caller.
+    ///
+    /// Seeds must be \["emitter"\].
+    #[account(
+        seeds = [Custodian::SEED_PREFIX],
+        bump = custodian.bump,
+    )]
+    custodian: Box<Account<'info, Custodian>>,
+
+    /// Circle-supported mint. Its owner selects the token program.
+    #[account(
+        address = USDC_MINT,
+        constraint = usdc_mint.to_account_info().owner == token_program.key @ TransferError::InvalidTokenProgram,
+    )]
+    usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Must equal CCTP Message Transmitter program ID.
+    #[account(address = MESSAGE_TRANSMITTER_PROGRAM_ID)]
+    message_transmitter_program: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["message_transmitter"\] (CCTP Message Transmitter program).
+    #[account(
+        seeds = [b"message_transmitter"],
+        seeds::program = MESSAGE_TRANSMITTER_PROGRAM_ID,
+        bump,
+    )]
+    message_transmitter: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["message_transmitter_authority", receiver\] (CCTP Message
+    /// Transmitter program).
+    #[account(
+        seeds = [b"message_transmitter_authority", TOKEN_MESSENGER_MINTER_PROGRAM_ID.as_ref()],
+        seeds::program = MESSAGE_TRANSMITTER_PROGRAM_ID,
+        bump,
+    )]
+    message_transmitter_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Mutable. Nonce bitmap covering the message nonce, validated by the CCTP Message
+    /// Transmitter program.
+    #[account(mut)]
+    used_nonces: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["__event_authority"\] (CCTP Message Transmitter program).
+    #[account(
+        seeds = [b"__event_authority"],
+        seeds::program = MESSAGE_TRANSMITTER_PROGRAM_ID,
+        bump,
+    )]
+    message_transmitter_event_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Must equal CCTP Token Messenger Minter program ID.
+    #[account(address = TOKEN_MESSENGER_MINTER_PROGRAM_ID)]
+    token_messenger_minter_program: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["token_messenger"\] (CCTP Token Messenger Minter program).
+    #[account(
+        seeds = [b"token_messenger"],
+        seeds::program = TOKEN_MESSENGER_MINTER_PROGRAM_ID,
+        bump,
+    )]
+    token_messenger: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["remote_token_messenger"\, remote_domain.to_string()] (CCTP Token
+    /// Messenger Minter program), validated by the CCTP Token Messenger Minter program.
+    remote_token_messenger: UncheckedAccount<'info>,
+
+    /// CHECK: Mutable. Seeds must be \["token_minter"\] (CCTP Token Messenger Minter program).
+    #[account(
+        mut,
+        seeds = [b"token_minter"],
+        seeds::program = TOKEN_MESSENGER_MINTER_PROGRAM_ID,
+        bump,
+    )]
+    token_minter: UncheckedAccount<'info>,
+
+    /// CHECK: Mutable. Seeds must be \["local_token", mint\] (CCTP Token Messenger Minter program).
+    #[account(
+        mut,
+        seeds = [b"local_token", USDC_MINT.as_ref()],
+        seeds::program = TOKEN_MESSENGER_MINTER_PROGRAM_ID,
+        bump,
+    )]
+    local_token: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["token_pair", remote_domain.to_string(), remote_token\] (CCTP Token
+    /// Messenger Minter program), validated by the CCTP Token Messenger Minter program.
+    token_pair: UncheckedAccount<'info>,
+
+    /// CHECK: Mutable. Seeds must be \["custody", mint\] (CCTP Token Messenger Minter program).
+    #[account(
+        mut,
+        seeds = [b"custody", USDC_MINT.as_ref()],
+        seeds::program = TOKEN_MESSENGER_MINTER_PROGRAM_ID,
+        bump,
+    )]
+    custody_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["__event_authority"\] (CCTP Token Messenger Minter program).
+    #[account(
+        seeds = [b"__event_authority"],
+        seeds::program = TOKEN_MESSENGER_MINTER_PROGRAM_ID,
+        bump,
+    )]
+    token_messenger_minter_event_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Safe because it's a sysvar account
+    #[account(address = sysvar::ID)]
+    pub signature: AccountInfo<'info>,
+}
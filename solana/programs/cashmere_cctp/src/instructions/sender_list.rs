@@ -0,0 +1,163 @@
+use std::mem::size_of;
+use anchor_lang::{
+    prelude::*,
+    system_program,
+};
+use crate::{
+    state::{
+        AllowedSender,
+        BlockedSender,
+        Config,
+        Role,
+        SenderList,
+    },
+    errors::ParamError,
+    events::SenderListChanged,
+};
+
+/// Adds `senders` to `list`. The entry PDAs, \[list prefix, sender\], are passed as remaining
+/// accounts in the same order. Senders already listed are skipped.
+pub fn add_senders_ix<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SenderListContext<'info>>,
+    list: SenderList,
+    senders: Vec<Pubkey>,
+) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Compliance), ParamError::AdminUnauthorized);
+    require!(ctx.remaining_accounts.len() == senders.len(), ParamError::InvalidSenderEntry);
+
+    for (sender, entry) in senders.into_iter().zip(ctx.remaining_accounts) {
+        let bump = entry_bump(list, &sender, entry)?;
+        if is_listed(entry) {
+            continue;
+        }
+
+        let space = 8 + match list {
+            SenderList::Blocked => size_of::<BlockedSender>(),
+            SenderList::Allowed => size_of::<AllowedSender>(),
+        };
+        let seeds: &[&[u8]] = &[list.seed_prefix(), sender.as_ref(), &[bump]];
+        create_entry(
+            &ctx.accounts.authority.to_account_info(),
+            entry,
+            &ctx.accounts.system_program.to_account_info(),
+            seeds,
+            space,
+        )?;
+
+        let mut data = entry.try_borrow_mut_data()?;
+        match list {
+            SenderList::Blocked => BlockedSender { sender, bump }.try_serialize(&mut &mut data[..])?,
+            SenderList::Allowed => AllowedSender { sender, bump }.try_serialize(&mut &mut data[..])?,
+        }
+
+        emit!(SenderListChanged {
+            list,
+            sender,
+            listed: true,
+            authority: ctx.accounts.authority.key(),
+        });
+    }
+    Ok(())
+}
+
+/// Removes `senders` from `list`, the rent of their entries goes to the authority. Senders that
+/// are not listed are skipped.
+pub fn remove_senders_ix<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SenderListContext<'info>>,
+    list: SenderList,
+    senders: Vec<Pubkey>,
+) -> Result<()> {
+    require!(ctx.accounts.config.has_role(&ctx.accounts.authority.key(), Role::Compliance), ParamError::AdminUnauthorized);
+    require!(ctx.remaining_accounts.len() == senders.len(), ParamError::InvalidSenderEntry);
+
+    for (sender, entry) in senders.into_iter().zip(ctx.remaining_accounts) {
+        entry_bump(list, &sender, entry)?;
+        if !is_listed(entry) {
+            continue;
+        }
+        let authority = ctx.accounts.authority.to_account_info();
+        match list {
+            SenderList::Blocked => Account::<BlockedSender>::try_from(entry)?.close(authority)?,
+            SenderList::Allowed => Account::<AllowedSender>::try_from(entry)?.close(authority)?,
+        }
+
+        emit!(SenderListChanged {
+            list,
+            sender,
+            listed: false,
+            authority: ctx.accounts.authority.key(),
+        });
+    }
+    Ok(())
+}
+
+/// Whether a sender list entry PDA exists.
+pub fn is_listed(entry: &AccountInfo) -> bool {
+    entry.owner == &crate::ID && !entry.data_is_empty()
+}
+
+fn entry_bump(list: SenderList, sender: &Pubkey, entry: &AccountInfo) -> Result<u8> {
+    let (address, bump) = Pubkey::find_program_address(&[list.seed_prefix(), sender.as_ref()], &crate::ID);
+    require_keys_eq!(entry.key(), address, ParamError::InvalidSenderEntry);
+    require!(entry.is_writable, ParamError::InvalidSenderEntry);
+    Ok(bump)
+}
+
+/// Creates a program-owned PDA, also when someone already sent lamports to its address.
+pub fn create_entry<'info>(
+    payer: &AccountInfo<'info>,
+    entry: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+    if entry.lamports() == 0 {
+        return system_program::create_account(CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: entry.clone(),
+            },
+            signer_seeds,
+        ), rent, space as u64, &crate::ID);
+    }
+
+    let missing = rent.saturating_sub(entry.lamports());
+    if missing > 0 {
+        system_program::transfer(CpiContext::new(
+            system_program.clone(),
+            system_program::Transfer {
+                from: payer.clone(),
+                to: entry.clone(),
+            },
+        ), missing)?;
+    }
+    system_program::allocate(CpiContext::new_with_signer(
+        system_program.clone(),
+        system_program::Allocate {
+            account_to_allocate: entry.clone(),
+        },
+        signer_seeds,
+    ), space as u64)?;
+    system_program::assign(CpiContext::new_with_signer(
+        system_program.clone(),
+        system_program::Assign {
+            account_to_assign: entry.clone(),
+        },
+        signer_seeds,
+    ), &crate::ID)
+}
+
+#[derive(Accounts)]
+pub struct SenderListContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    // Owner or compliance, pays for and receives the rent of the entries
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+use crate::{
+    state::{
+        Config,
+        ConfigChange,
+        DomainConfig,
+        Partner,
+        PendingChange,
+    },
+    errors::ParamError,
+    events::{
+        ConfigChangeQueued,
+        ConfigChangeExecuted,
+        ConfigChangeCancelled,
+        SignerKeyChanged,
+        SignerSetChanged,
+        GasDropCollectorChanged,
+        TimelockDelayChanged,
+        DomainFeesChanged,
+        RequireFullQuoteChanged,
+        PartnerChanged,
+    },
+    utils::BP,
+};
+
+/// Queues `change`, it can be executed `timelock_delay` seconds from now. Changes already queued
+/// keep their eta when the delay itself changes.
+pub fn queue_config_change_ix(ctx: Context<QueueConfigChangeContext>, change: ConfigChange) -> Result<()> {
+    require!(can_manage(&ctx.accounts.config, &ctx.accounts.authority.key(), &change), ParamError::AdminUnauthorized);
+    validate_change(&change)?;
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let config = &mut ctx.accounts.config;
+    let id = config.next_change_id;
+    let eta = now + config.timelock_delay;
+    config.next_change_id += 1;
+
+    emit!(ConfigChangeQueued {
+        id,
+        change: change.clone(),
+        eta,
+        proposer: ctx.accounts.authority.key(),
+    });
+    ctx.accounts.pending_change.set_inner(PendingChange {
+        id,
+        change,
+        proposer: ctx.accounts.authority.key(),
+        eta,
+        bump: ctx.bumps.pending_change,
+    });
+    Ok(())
+}
+
+/// Applies a queued change once its eta has passed. Anyone can execute it, the rent goes back to
+/// the proposer.
+pub fn execute_config_change_ix(ctx: Context<ExecuteConfigChangeContext>, id: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(now >= ctx.accounts.pending_change.eta, ParamError::TimelockNotExpired);
+
+    let change = ctx.accounts.pending_change.change.clone();
+    apply_change(
+        &mut ctx.accounts.config,
+        ctx.accounts.domain_config.as_deref_mut(),
+        ctx.accounts.partner.as_deref_mut(),
+        &change,
+        ctx.accounts.proposer.key(),
+        now,
+    )?;
+    emit!(ConfigChangeExecuted {
+        id,
+        change,
+        executor: ctx.accounts.executor.key(),
+    });
+    Ok(())
+}
+
+pub fn cancel_config_change_ix(ctx: Context<CancelConfigChangeContext>, id: u64) -> Result<()> {
+    let change = ctx.accounts.pending_change.change.clone();
+    require!(can_manage(&ctx.accounts.config, &ctx.accounts.authority.key(), &change), ParamError::AdminUnauthorized);
+    emit!(ConfigChangeCancelled {
+        id,
+        change,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+fn can_manage(config: &Config, key: &Pubkey, change: &ConfigChange) -> bool {
+    match change.role() {
+        Some(role) => config.has_role(key, role),
+        None => *key == config.owner,
+    }
+}
+
+fn validate_change(change: &ConfigChange) -> Result<()> {
+    match change {
+        // A non-zero threshold requires that many signatures from the set instead of the signer
+        // key; an empty set with a zero threshold switches back to the signer key.
+        ConfigChange::SignerSet { signers, threshold } => {
+            require!(signers.len() <= Config::MAX_SIGNERS, ParamError::InvalidSignerSet);
+            require!(*threshold as usize <= signers.len(), ParamError::InvalidSignerSet);
+            require!((*threshold == 0) == signers.is_empty(), ParamError::InvalidSignerSet);
+            for (i, signer) in signers.iter().enumerate() {
+                require!(*signer != [0; 32], ParamError::InvalidSignerSet);
+                require!(!signers[..i].contains(signer), ParamError::InvalidSignerSet);
+            }
+        }
+        ConfigChange::SignerKey { signer_key, previous_key_overlap } => {
+            require!(*signer_key != [0; 32], ParamError::InvalidSignerKey);
+            require!(*previous_key_overlap <= Config::MAX_SIGNER_KEY_OVERLAP, ParamError::InvalidSignerKey);
+        }
+        ConfigChange::TimelockDelay { timelock_delay } => {
+            require!(*timelock_delay <= Config::MAX_TIMELOCK_DELAY, ParamError::InvalidTimelockDelay);
+        }
+        ConfigChange::DomainFees { fees, .. } => {
+            require!(fees.fee_bp <= 100, ParamError::FeeTooHigh);
+            require!(fees.fee_tiers.len() <= DomainConfig::MAX_FEE_TIERS, ParamError::InvalidFeeTiers);
+            for (i, tier) in fees.fee_tiers.iter().enumerate() {
+                require!(tier.fee_bp <= 100, ParamError::FeeTooHigh);
+                require!(i == 0 || tier.min_amount > fees.fee_tiers[i - 1].min_amount, ParamError::InvalidFeeTiers);
+            }
+            require!(
+                fees.max_percentage_fee == 0 || fees.min_percentage_fee <= fees.max_percentage_fee,
+                ParamError::InvalidFeeTiers,
+            );
+        }
+        ConfigChange::Partner { share_bp, .. } => {
+            require!(*share_bp <= BP, ParamError::PartnerShareTooHigh);
+        }
+        ConfigChange::GasDropCollector { .. } | ConfigChange::RequireFullQuote { .. } => {}
+    }
+    Ok(())
+}
+
+fn apply_change(
+    config: &mut Config,
+    domain_config: Option<&mut DomainConfig>,
+    partner: Option<&mut Partner>,
+    change: &ConfigChange,
+    authority: Pubkey,
+    now: u64,
+) -> Result<()> {
+    match change {
+        // The outgoing key stays valid for `previous_key_overlap` seconds from execution, so quotes
+        // already in flight still land; a zero overlap ends it with the current second. The unset
+        // initial key is never carried over.
+        ConfigChange::SignerKey { signer_key, previous_key_overlap } => {
+            let (previous_signer_key, previous_signer_key_expiry) = if config.signer_key == [0; 32] {
+                ([0; 32], 0)
+            } else {
+                (config.signer_key, now + previous_key_overlap)
+            };
+            emit!(SignerKeyChanged {
+                old_signer_key: config.signer_key,
+                new_signer_key: *signer_key,
+                previous_signer_key_expiry,
+                authority,
+            });
+            config.previous_signer_key = previous_signer_key;
+            config.previous_signer_key_expiry = previous_signer_key_expiry;
+            config.signer_key = *signer_key;
+        }
+        ConfigChange::SignerSet { signers, threshold } => {
+            emit!(SignerSetChanged {
+                old_signers: config.signer_set().to_vec(),
+                new_signers: signers.clone(),
+                old_threshold: config.signer_threshold,
+                new_threshold: *threshold,
+                authority,
+            });
+            config.signer_set = [[0; 32]; Config::MAX_SIGNERS];
+            config.signer_set[..signers.len()].copy_from_slice(signers);
+            config.signer_set_len = signers.len() as u8;
+            config.signer_threshold = *threshold;
+        }
+        ConfigChange::GasDropCollector { gas_drop_collector_sol, gas_drop_collector_usdc } => {
+            emit!(GasDropCollectorChanged {
+                old_gas_drop_collector_sol: config.gas_drop_collector_sol,
+                new_gas_drop_collector_sol: *gas_drop_collector_sol,
+                old_gas_drop_collector_usdc: config.gas_drop_collector_usdc,
+                new_gas_drop_collector_usdc: *gas_drop_collector_usdc,
+                authority,
+            });
+            config.gas_drop_collector_sol = *gas_drop_collector_sol;
+            config.gas_drop_collector_usdc = *gas_drop_collector_usdc;
+        }
+        ConfigChange::TimelockDelay { timelock_delay } => {
+            emit!(TimelockDelayChanged {
+                old_timelock_delay: config.timelock_delay,
+                new_timelock_delay: *timelock_delay,
+                authority,
+            });
+            config.timelock_delay = *timelock_delay;
+        }
+        ConfigChange::DomainFees { domain, fees } => {
+            let domain_config = domain_config
+                .filter(|domain_config| domain_config.domain == *domain)
+                .ok_or(ParamError::InvalidDomain)?;
+            emit!(DomainFeesChanged {
+                domain: *domain,
+                old_fees: domain_config.fees(),
+                new_fees: fees.clone(),
+                authority,
+            });
+            domain_config.set_fees(fees);
+        }
+        ConfigChange::RequireFullQuote { require_full_quote } => {
+            emit!(RequireFullQuoteChanged {
+                old_require_full_quote: config.require_full_quote,
+                new_require_full_quote: *require_full_quote,
+                authority,
+            });
+            config.require_full_quote = *require_full_quote;
+        }
+        ConfigChange::Partner { partner: partner_key, share_bp, payout } => {
+            let partner = partner
+                .filter(|partner| partner.partner == *partner_key)
+                .ok_or(ParamError::InvalidPartner)?;
+            emit!(PartnerChanged {
+                partner: *partner_key,
+                old_share_bp: partner.share_bp,
+                new_share_bp: *share_bp,
+                old_payout: partner.payout,
+                new_payout: *payout,
+                authority,
+            });
+            partner.share_bp = *share_bp;
+            partner.payout = *payout;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct QueueConfigChangeContext<'info> {
+    #[account(mut, seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PendingChange::INIT_SPACE + 8,
+        seeds = [PendingChange::SEED_PREFIX, &config.next_change_id.to_le_bytes()],
+        bump,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    // Owner or holder of the role of the change, pays the rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct ExecuteConfigChangeContext<'info> {
+    #[account(mut, seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [PendingChange::SEED_PREFIX, &id.to_le_bytes()],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Must be the proposer of the change, receives the rent.
+    #[account(mut, address = pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Domain of a `ConfigChange::DomainFees`.
+    #[account(mut)]
+    pub domain_config: Option<Account<'info, DomainConfig>>,
+
+    /// Partner of a `ConfigChange::Partner`.
+    #[account(mut)]
+    pub partner: Option<Account<'info, Partner>>,
+
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CancelConfigChangeContext<'info> {
+    #[account(seeds=[b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [PendingChange::SEED_PREFIX, &id.to_le_bytes()],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Must be the proposer of the change, receives the rent.
+    #[account(mut, address = pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    // Owner or holder of the role of the change
+    pub authority: Signer<'info>,
+}
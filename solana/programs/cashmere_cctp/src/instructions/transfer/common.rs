@@ -1,26 +1,63 @@
+use std::mem::size_of;
 use anchor_lang::{
     prelude::*,
+    solana_program::hash::hash,
     system_program,
 };
 use borsh::{BorshSerialize, to_vec};
 use crate::{
-    state::{Config, Custodian},
-    errors::{
-        TransferError,
-        ParamError,
-    },
+    instructions::{create_entry, is_listed},
+    state::{Config, Custodian, DomainConfig, Lane, Partner, RateLimit, RateLimitBucket, UsedQuote},
+    errors::{OracleError, TransferError},
     utils::{
-        verify_ed25519_ix,
+        verify_quote_signature,
         calculate_fee,
+        FeeBreakdown,
+        PythPrice,
     },
 };
-use anchor_spl::token::{
+use anchor_spl::token_interface::{
     self,
-    Transfer as SplTransfer
+    Mint,
+    TokenAccount,
+    TransferChecked,
 };
 
+/// Version tag of the full signed quote, see [`TransferParams`].
+pub const QUOTE_VERSION: u8 = 3;
+
+/// Signed quote payload. Commits to every parameter of the transfer so that a quote can only be
+/// used by the `owner` it was issued for, with the exact amount, recipient and gas drop.
 #[derive(BorshSerialize)]
 pub struct TransferParams {
+    pub version: u8,
+    pub program_id: Pubkey,
+    pub cctp_version: u8,
+    pub local_domain: u32,
+    pub destination_domain: u32,
+    pub owner: Pubkey,
+    pub quote_id: u64,
+    pub usdc_amount: u64,
+    pub recipient: [u8; 32],
+    pub solana_owner: [u8; 32],
+    pub fee: u64,
+    pub deadline: u64,
+    pub gas_drop_amount: u64,
+    pub fee_is_native: bool,
+    pub max_fee: u64,
+    pub min_finality_threshold: u32,
+    pub hook_data_hash: [u8; 32], // keccak256 of the CCTP v2 hook data, zero without hook
+    pub destination_caller: [u8; 32], // override of the domain's destination caller, zero for none
+    pub partner: Pubkey, // partner sharing the percentage fee, default for none
+    pub gas_drop_priced: bool, // native gas drop signed in micro-USDC, see `Config::gas_drop_priced`
+}
+
+/// Legacy signed quote payload, only accepted while `Config::require_full_quote` is off. It does
+/// not carry a quote id, so the `quote_id` of a legacy transfer is chosen freely by the sender and
+/// replays are only stopped by the hash of these bytes. It signs neither hook data, destination
+/// caller nor partner, transfers using any of them need the full quote.
+#[derive(BorshSerialize)]
+pub struct LegacyTransferParams {
     cctp_version: u8,
     local_domain: u32,
     destination_domain: u32,
@@ -29,69 +66,319 @@ pub struct TransferParams {
     fee_is_native: bool,
 }
 
+impl TransferParams {
+    /// Legacy payload of the transfer, `None` when it uses a parameter the legacy format does not
+    /// sign.
+    fn legacy(&self) -> Option<LegacyTransferParams> {
+        let unsigned_params = self.hook_data_hash != [0; 32]
+            || self.destination_caller != [0; 32]
+            || self.partner != Pubkey::default();
+        if unsigned_params {
+            return None;
+        }
+        Some(LegacyTransferParams {
+            cctp_version: self.cctp_version,
+            local_domain: self.local_domain,
+            destination_domain: self.destination_domain,
+            fee: self.fee,
+            deadline: self.deadline,
+            fee_is_native: self.fee_is_native,
+        })
+    }
+}
+
+/// Transfer limit that a transfer breaks, shared by `pre_transfer` and the `quote` view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum QuoteViolation {
+    Paused,
+    LanePaused(Lane),
+    DomainDisabled,
+    CctpVersionNotAllowed,
+    AmountBelowMinimum,
+    AmountAboveMaximum,
+    FeeExceedsAmount,
+    FeeBelowMinimum,
+    GasDropLimitExceeded,
+    GlobalRateLimitExceeded,
+    DomainRateLimitExceeded,
+    SenderBlocked,
+    SenderNotAllowed,
+}
+
+impl From<QuoteViolation> for Error {
+    fn from(violation: QuoteViolation) -> Self {
+        match violation {
+            QuoteViolation::Paused => TransferError::Paused.into(),
+            QuoteViolation::LanePaused(lane) => lane_paused_error(lane),
+            QuoteViolation::DomainDisabled => TransferError::DomainDisabled.into(),
+            QuoteViolation::CctpVersionNotAllowed => TransferError::CctpVersionNotAllowed.into(),
+            QuoteViolation::AmountBelowMinimum => TransferError::AmountBelowMinimum.into(),
+            QuoteViolation::AmountAboveMaximum => TransferError::AmountAboveMaximum.into(),
+            QuoteViolation::FeeExceedsAmount => TransferError::FeeExceedsAmount.into(),
+            QuoteViolation::FeeBelowMinimum => TransferError::FeeBelowMinimum.into(),
+            QuoteViolation::GasDropLimitExceeded => TransferError::GasDropLimitExceeded.into(),
+            QuoteViolation::GlobalRateLimitExceeded => TransferError::GlobalRateLimitExceeded.into(),
+            QuoteViolation::DomainRateLimitExceeded => TransferError::DomainRateLimitExceeded.into(),
+            QuoteViolation::SenderBlocked => TransferError::SenderBlocked.into(),
+            QuoteViolation::SenderNotAllowed => TransferError::SenderNotAllowed.into(),
+        }
+    }
+}
+
+/// Error of an operation on a paused lane.
+pub fn lane_paused_error(lane: Lane) -> Error {
+    match lane {
+        Lane::CctpV1 => TransferError::CctpV1Paused.into(),
+        Lane::CctpV2 => TransferError::CctpV2Paused.into(),
+        Lane::NativeFee => TransferError::NativeFeePaused.into(),
+        Lane::UsdcFee => TransferError::UsdcFeePaused.into(),
+        Lane::GasDrop => TransferError::GasDropPaused.into(),
+    }
+}
+
+/// Sender list check of a transfer, given the sender's denylist and allowlist entry PDAs.
+pub fn sender_violation(config: &Config, blocked_sender: &AccountInfo, allowed_sender: &AccountInfo) -> Option<QuoteViolation> {
+    if is_listed(blocked_sender) {
+        Some(QuoteViolation::SenderBlocked)
+    } else if config.allowlist_only && !is_listed(allowed_sender) {
+        Some(QuoteViolation::SenderNotAllowed)
+    } else {
+        None
+    }
+}
+
+/// First limit broken by a transfer with the given fees, if any.
+pub fn transfer_violation(
+    config: &Config,
+    domain_config: &DomainConfig,
+    cctp_version: u8,
+    usdc_amount: u64,
+    fees: &FeeBreakdown,
+    fee_is_native: bool,
+) -> Option<QuoteViolation> {
+    // the global caps bound every domain
+    let gas_drop_limit = if fee_is_native {
+        domain_config.max_native_gas_drop.min(config.max_native_gas_drop)
+    } else {
+        domain_config.max_usdc_gas_drop.min(config.max_usdc_gas_drop)
+    };
+
+    let paused_lane = [
+        Lane::cctp(cctp_version),
+        Some(Lane::fee(fee_is_native)),
+        (fees.gas_drop > 0).then_some(Lane::GasDrop),
+    ]
+        .into_iter()
+        .flatten()
+        .find(|lane| config.lane_paused(*lane));
+
+    if config.paused {
+        Some(QuoteViolation::Paused)
+    } else if let Some(lane) = paused_lane {
+        Some(QuoteViolation::LanePaused(lane))
+    } else if !domain_config.enabled {
+        Some(QuoteViolation::DomainDisabled)
+    } else if !domain_config.allows_cctp_version(cctp_version) {
+        Some(QuoteViolation::CctpVersionNotAllowed)
+    } else if usdc_amount < config.min_transfer_amount {
+        Some(QuoteViolation::AmountBelowMinimum)
+    } else if config.max_transfer_amount > 0 && usdc_amount > config.max_transfer_amount {
+        Some(QuoteViolation::AmountAboveMaximum)
+    } else if !matches!(fees.usdc_fee(fee_is_native), Some(usdc_fee) if usdc_fee <= usdc_amount) {
+        Some(QuoteViolation::FeeExceedsAmount)
+    } else if !fee_is_native && fees.static_fee < domain_config.min_fee {
+        Some(QuoteViolation::FeeBelowMinimum)
+    } else if fees.gas_drop > gas_drop_limit {
+        Some(QuoteViolation::GasDropLimitExceeded)
+    } else {
+        None
+    }
+}
+
+/// Consumes `amount` from the global and the domain outflow buckets, returns the first one that is
+/// short. The `quote` view passes copies of the buckets.
+pub fn rate_limit_violation(
+    global: &mut RateLimitBucket,
+    domain: &mut RateLimitBucket,
+    amount: u64,
+    now: i64,
+) -> Option<QuoteViolation> {
+    if !global.consume(amount, now) {
+        Some(QuoteViolation::GlobalRateLimitExceeded)
+    } else if !domain.consume(amount, now) {
+        Some(QuoteViolation::DomainRateLimitExceeded)
+    } else {
+        None
+    }
+}
+
+/// Gas drop charged for a transfer. With a gas drop oracle configured, a native gas drop is signed
+/// in micro-USDC and converted to lamports at the oracle price. The quote commits to the pricing
+/// mode, so switching the oracle on or off invalidates the quotes signed before.
+pub fn charged_gas_drop(
+    config: &Config,
+    price_oracle: Option<&AccountInfo>,
+    gas_drop_amount: u64,
+    fee_is_native: bool,
+    now: i64,
+) -> Result<u64> {
+    if !fee_is_native || gas_drop_amount == 0 || !config.gas_drop_priced() {
+        return Ok(gas_drop_amount);
+    }
+    let price_oracle = price_oracle
+        .filter(|account| account.key() == config.gas_drop_oracle)
+        .ok_or(OracleError::InvalidOracle)?;
+    let price = PythPrice::load(price_oracle)?;
+    price.validate(now, config.max_oracle_price_age, config.max_oracle_confidence_bp)?;
+    price.usdc_to_lamports(gas_drop_amount)
+}
+
+/// Verifies the quote and collects the fees of a transfer, then moves the rest into
+/// `burn_token_account`. Returns the amount to burn and the gas drop charged.
 pub fn pre_transfer<'info>(
     config: &Config,
+    domain_config: &mut DomainConfig,
+    rate_limit: &mut RateLimit,
     signature: &AccountInfo<'info>,
-    owner_token_account: &AccountInfo<'info>,
+    owner_token_account: &InterfaceAccount<'info, TokenAccount>,
     burn_token_account: &AccountInfo<'info>,
-    fee_collector_sol_account: &AccountInfo<'info>,
-    fee_collector_usdc_account: &AccountInfo<'info>,
+    fee_vault: &AccountInfo<'info>,
+    fee_vault_token_account: &AccountInfo<'info>,
     gas_drop_collector_sol_account: &AccountInfo<'info>,
     gas_drop_collector_usdc_account: &AccountInfo<'info>,
     owner: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    relayer_fee_account: Option<&AccountInfo<'info>>,
+    partner: Option<&Partner>,
+    partner_payout_account: Option<&AccountInfo<'info>>,
+    price_oracle: Option<&AccountInfo<'info>>,
+    blocked_sender: &AccountInfo<'info>,
+    allowed_sender: &AccountInfo<'info>,
+    used_quote: &AccountInfo<'info>,
     custodian: &Account<'info, Custodian>,
+    burn_token_mint: &InterfaceAccount<'info, Mint>,
     token_program: &AccountInfo<'info>,
     system_program: &AccountInfo<'info>,
-    usdc_amount: u64,
-    destination_domain: u32,
-    fee: u64,
-    deadline: u64,
-    gas_drop_amount: u64,
-    fee_is_native: bool,
-    cctp_version: u8,
-) -> Result<u64> {
-    require!(destination_domain < 32, ParamError::InvalidDomain);
-    require!(!config.paused, TransferError::Paused);
-
-    let msg = TransferParams {
-        cctp_version,
-        local_domain: 5,
-        destination_domain,
+    params: &TransferParams,
+    ed25519_ix_index: u16,
+) -> Result<(u64, u64)> {
+    let TransferParams {
+        usdc_amount,
         fee,
         deadline,
+        gas_drop_amount,
         fee_is_native,
-    };
-    let msg_bytes = to_vec(&msg)?;
-    let ed25519_ix = &signature.to_account_info();
-    match verify_ed25519_ix(ed25519_ix, &msg_bytes, &config.signer_key) {
-        Err(e) => return Err(e),
-        _ => {},
-    };
+        ..
+    } = *params;
+
+    if let Some(violation) = sender_violation(config, blocked_sender, allowed_sender) {
+        return Err(violation.into());
+    }
 
     let clock = Clock::get()?;
-    if clock.unix_timestamp as u64 > deadline {
-        return Err(TransferError::DeadlineExpired.into());
+
+    let gas_drop = charged_gas_drop(config, price_oracle, gas_drop_amount, fee_is_native, clock.unix_timestamp)?;
+    let fees = FeeBreakdown::new(domain_config, usdc_amount, fee, gas_drop, fee_is_native);
+    if let Some(violation) = transfer_violation(config, domain_config, params.cctp_version, usdc_amount, &fees, fee_is_native) {
+        return Err(violation.into());
     }
+    let usdc_fee_amount = fees.usdc_fee(fee_is_native).ok_or(TransferError::FeeExceedsAmount)?;
 
-    let usdc_fee_amount = calculate_fee(usdc_amount, config.fee_bp, if fee_is_native { 0 } else { fee });
-    require!(usdc_amount >= usdc_fee_amount, TransferError::FeeExceedsAmount);
-    if fee_is_native {
-        let native_gas_drop_limit = config.max_native_gas_drop;
-        require!(gas_drop_amount <= native_gas_drop_limit, TransferError::GasDropLimitExceeded);
+    // relayed mode: the owner does not sign, the payer moves its tokens as approved delegate and
+    // is reimbursed with the static fee
+    let relayed = !owner.is_signer;
+    let authority = if relayed {
+        require!(
+            owner_token_account.delegate == Some(payer.key()).into(),
+            TransferError::RelayerNotDelegate,
+        );
+        require!(!fee_is_native, TransferError::NativeFeeNotRelayable);
+        payer
     } else {
-        let usdc_gas_drop_limit = config.max_usdc_gas_drop;
-        require!(gas_drop_amount <= usdc_gas_drop_limit, TransferError::GasDropLimitExceeded);
+        owner
+    };
+
+    let ed25519_ix = &signature.to_account_info();
+    let now = clock.unix_timestamp as u64;
+    let mut message = to_vec(params)?;
+    let verified = verify_quote_signature(ed25519_ix, ed25519_ix_index, &message, config, now);
+    // a partner diverts part of the fee, only a full quote may name one
+    let legacy = params.legacy().filter(|_| !config.require_full_quote && partner.is_none());
+    match (verified, legacy) {
+        (Err(_), Some(legacy)) => {
+            message = to_vec(&legacy)?;
+            verify_quote_signature(ed25519_ix, ed25519_ix_index, &message, config, now)?;
+        }
+        (verified, _) => verified?,
+    }
+
+    // mark the signed message as consumed
+    let quote_hash = hash(&message).to_bytes();
+    let (used_quote_address, used_quote_bump) = Pubkey::find_program_address(&[UsedQuote::SEED_PREFIX, &quote_hash], &crate::ID);
+    require_keys_eq!(used_quote.key(), used_quote_address, TransferError::InvalidUsedQuote);
+    require!(!is_listed(used_quote), TransferError::QuoteAlreadyUsed);
+    create_entry(
+        payer,
+        used_quote,
+        system_program,
+        &[UsedQuote::SEED_PREFIX, &quote_hash, &[used_quote_bump]],
+        size_of::<UsedQuote>() + 8,
+    )?;
+    UsedQuote {
+        rent_payer: payer.key(),
+        deadline,
+        bump: used_quote_bump,
+    }.try_serialize(&mut &mut used_quote.try_borrow_mut_data()?[..])?;
+
+    if clock.unix_timestamp as u64 > deadline {
+        return Err(TransferError::DeadlineExpired.into());
     }
 
     // collect fee in USDC
-    token::transfer(CpiContext::new(
+    let relayer_fee_amount = if relayed { fee } else { 0 };
+    let partner_fee_amount = match partner {
+        Some(partner) => calculate_fee(fees.percentage_fee, partner.share_bp, 0),
+        None => 0,
+    };
+    token_interface::transfer_checked(CpiContext::new(
         token_program.to_account_info(),
-        SplTransfer {
+        TransferChecked {
             from: owner_token_account.to_account_info(),
-            to: fee_collector_usdc_account.to_account_info(),
-            authority: owner.to_account_info(),
+            mint: burn_token_mint.to_account_info(),
+            to: fee_vault_token_account.to_account_info(),
+            authority: authority.to_account_info(),
         },
-    ), usdc_fee_amount)?;
+    ), usdc_fee_amount - relayer_fee_amount - partner_fee_amount, burn_token_mint.decimals)?;
+
+    // pay the partner share of the percentage fee in USDC
+    if partner_fee_amount > 0 {
+        let partner_payout_account = partner_payout_account
+            .filter(|account| Some(account.key()) == partner.map(|partner| partner.payout))
+            .ok_or(TransferError::InvalidPartnerPayout)?;
+        token_interface::transfer_checked(CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: owner_token_account.to_account_info(),
+                mint: burn_token_mint.to_account_info(),
+                to: partner_payout_account.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+        ), partner_fee_amount, burn_token_mint.decimals)?;
+    }
+
+    // reimburse the relayer in USDC
+    if relayer_fee_amount > 0 {
+        let relayer_fee_account = relayer_fee_account.ok_or(TransferError::MissingRelayerFeeAccount)?;
+        token_interface::transfer_checked(CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: owner_token_account.to_account_info(),
+                mint: burn_token_mint.to_account_info(),
+                to: relayer_fee_account.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+        ), relayer_fee_amount, burn_token_mint.decimals)?;
+    }
 
     if fee_is_native {
         // collect fee in SOL
@@ -99,47 +386,52 @@ pub fn pre_transfer<'info>(
             system_program.to_account_info(),
             system_program::Transfer {
                 from: owner.to_account_info(),
-                to: fee_collector_sol_account.to_account_info(),
+                to: fee_vault.to_account_info(),
             },
         ), fee)?;
         // collect gas drop in SOL
-        if gas_drop_amount > 0 {
+        if fees.gas_drop > 0 {
             system_program::transfer(CpiContext::new(
                 system_program.to_account_info(),
                 system_program::Transfer {
                     from: owner.to_account_info(),
                     to: gas_drop_collector_sol_account.to_account_info(),
                 },
-            ), gas_drop_amount)?;
+            ), fees.gas_drop)?;
         }
     } else {
         // collect gas drop in USDC
-        if gas_drop_amount > 0 {
-            token::transfer(CpiContext::new(
+        if fees.gas_drop > 0 {
+            token_interface::transfer_checked(CpiContext::new(
                 token_program.to_account_info(),
-                SplTransfer {
+                TransferChecked {
                     from: owner_token_account.to_account_info(),
+                    mint: burn_token_mint.to_account_info(),
                     to: gas_drop_collector_usdc_account.to_account_info(),
-                    authority: owner.to_account_info(),
+                    authority: authority.to_account_info(),
                 },
-            ), gas_drop_amount)?;
+            ), fees.gas_drop, burn_token_mint.decimals)?;
         }
     }
 
     let amount = usdc_amount - usdc_fee_amount;
+    if let Some(violation) = rate_limit_violation(&mut rate_limit.bucket, &mut domain_config.rate_limit, amount, clock.unix_timestamp) {
+        return Err(violation.into());
+    }
 
     let custodian_seeds: &[&[&[u8]]] = &[&[Custodian::SEED_PREFIX, &[custodian.bump]]];
 
     // transfer the rest
-    token::transfer(CpiContext::new_with_signer(
+    token_interface::transfer_checked(CpiContext::new_with_signer(
         token_program.to_account_info(),
-        SplTransfer {
+        TransferChecked {
             from: owner_token_account.to_account_info(),
+            mint: burn_token_mint.to_account_info(),
             to: burn_token_account.to_account_info(),
-            authority: owner.to_account_info(),
+            authority: authority.to_account_info(),
         },
         custodian_seeds,
-    ), amount)?;
+    ), amount, burn_token_mint.decimals)?;
 
-    Ok(amount)
+    Ok((amount, fees.gas_drop))
 }
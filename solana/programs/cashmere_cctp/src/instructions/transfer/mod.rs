@@ -1,7 +1,19 @@
 mod transfer_ix;
 mod transfer_v2_ix;
+mod transfer_v2_with_hook_ix;
 mod common;
 
 pub use transfer_ix::*;
 pub use transfer_v2_ix::*;
-pub use common::pre_transfer;
+pub use transfer_v2_with_hook_ix::*;
+pub use common::{
+    pre_transfer,
+    charged_gas_drop,
+    transfer_violation,
+    rate_limit_violation,
+    sender_violation,
+    lane_paused_error,
+    QuoteViolation,
+    TransferParams,
+    QUOTE_VERSION,
+};
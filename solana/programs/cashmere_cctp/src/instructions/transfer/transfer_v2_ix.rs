@@ -1,31 +1,46 @@
 use anchor_lang::{prelude::*, solana_program::{
     self,
+    keccak,
     sysvar::instructions as sysvar,
 }};
-use anchor_spl::token::{
+use anchor_spl::token_interface::{
     self,
-    Token,
+    Mint,
     TokenAccount,
+    TokenInterface,
 };
 use crate::{
     state::{
         Custodian,
         Config,
+        FeeVault,
+        Partner,
+        RateLimit,
+        BlockedSender,
+        AllowedSender,
+        DomainConfig,
     },
     events::TransferEvent,
+    errors::TransferError,
     cctp::{
         TOKEN_MESSENGER_MINTER_V2_PROGRAM_ID,
         MESSAGE_TRANSMITTER_V2_PROGRAM_ID,
         USDC_MINT,
+        LOCAL_DOMAIN,
         token_messenger_minter_program_v2::{
             cpi::{
                 DepositForBurn,
                 DepositForBurnParams,
+                DepositForBurnWithHookParams,
             },
         },
     },
 };
-use super::pre_transfer;
+use super::{
+    pre_transfer,
+    TransferParams,
+    QUOTE_VERSION,
+};
 
 
 
@@ -34,6 +49,7 @@ fee structure:
 - percentage fee is always taken in USDC
 - server-side fee is taken either in USDC or SOL, depending on `fee_is_native`
 - gas drop is taken either in USDC or SOL, depending on `fee_is_native`
+- relayed transfers (owner does not sign) pay all fees in USDC, the server-side fee goes to the relayer
 */
 
 pub fn transfer_v2_ix(
@@ -48,27 +64,99 @@ pub fn transfer_v2_ix(
     fee_is_native: bool,
     max_fee: u64,
     min_finality_threshold: u32,
+    quote_id: u64,
+    destination_caller: [u8; 32],
+    ed25519_ix_index: u16,
 ) -> Result<()> {
-    let amount = pre_transfer(
+    process_transfer_v2(
+        ctx,
+        usdc_amount,
+        destination_domain,
+        recipient,
+        solana_owner,
+        fee,
+        deadline,
+        gas_drop_amount,
+        fee_is_native,
+        max_fee,
+        min_finality_threshold,
+        quote_id,
+        destination_caller,
+        None,
+        ed25519_ix_index,
+    )
+}
+
+/// Shared by `transfer_v2` and `transfer_v2_with_hook`, burns with `depositForBurnWithHook` when
+/// `hook_data` is set.
+pub(super) fn process_transfer_v2(
+    ctx: Context<TransferV2Context>,
+    usdc_amount: u64,
+    destination_domain: u32,
+    recipient: [u8; 32],
+    solana_owner: [u8; 32],
+    fee: u64,
+    deadline: u64,
+    gas_drop_amount: u64,
+    fee_is_native: bool,
+    max_fee: u64,
+    min_finality_threshold: u32,
+    quote_id: u64,
+    destination_caller: [u8; 32],
+    hook_data: Option<Vec<u8>>,
+    ed25519_ix_index: u16,
+) -> Result<()> {
+    let hook_data_hash = hook_data.as_ref()
+        .map(|hook_data| keccak::hash(hook_data).to_bytes())
+        .unwrap_or_default();
+    let params = TransferParams {
+        version: QUOTE_VERSION,
+        program_id: crate::ID,
+        cctp_version: 2,
+        local_domain: LOCAL_DOMAIN,
+        destination_domain,
+        owner: ctx.accounts.owner.key(),
+        quote_id,
+        usdc_amount,
+        recipient,
+        solana_owner,
+        fee,
+        deadline,
+        gas_drop_amount,
+        fee_is_native,
+        max_fee,
+        min_finality_threshold,
+        hook_data_hash,
+        destination_caller,
+        partner: ctx.accounts.partner.as_ref().map(|partner| partner.partner).unwrap_or_default(),
+        gas_drop_priced: ctx.accounts.config.gas_drop_priced(),
+    };
+    let (amount, gas_drop) = pre_transfer(
         &ctx.accounts.config,
+        &mut ctx.accounts.domain_config,
+        &mut ctx.accounts.rate_limit,
         &ctx.accounts.signature.to_account_info(),
-        &ctx.accounts.owner_token_account.to_account_info(),
+        &ctx.accounts.owner_token_account,
         &ctx.accounts.burn_token_account.to_account_info(),
-        &ctx.accounts.fee_collector_sol_account.to_account_info(),
-        &ctx.accounts.fee_collector_usdc_account.to_account_info(),
+        &ctx.accounts.fee_vault.to_account_info(),
+        &ctx.accounts.fee_vault_token_account.to_account_info(),
         &ctx.accounts.gas_drop_collector_sol_account.to_account_info(),
         &ctx.accounts.gas_drop_collector_usdc_account.to_account_info(),
         &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        ctx.accounts.relayer_fee_account.as_deref().map(AsRef::as_ref),
+        ctx.accounts.partner.as_deref().map(|partner| &**partner),
+        ctx.accounts.partner_payout_account.as_deref().map(AsRef::as_ref),
+        ctx.accounts.price_oracle.as_deref(),
+        &ctx.accounts.blocked_sender.to_account_info(),
+        &ctx.accounts.allowed_sender.to_account_info(),
+        &ctx.accounts.used_quote.to_account_info(),
         &ctx.accounts.custodian,
+        &ctx.accounts.burn_token_mint,
         &ctx.accounts.token_program.to_account_info(),
         &ctx.accounts.system_program.to_account_info(),
-        usdc_amount,
-        destination_domain,
-        fee,
-        deadline,
-        gas_drop_amount,
-        fee_is_native,
-        2,
+        &params,
+        ed25519_ix_index,
     )?;
     let custodian_seeds: &[&[&[u8]]] = &[&[Custodian::SEED_PREFIX, &[ctx.accounts.custodian.bump]]];
 
@@ -76,7 +164,7 @@ pub fn transfer_v2_ix(
         ctx.accounts.token_messenger_minter_program.to_account_info(),
         DepositForBurn {
             owner: ctx.accounts.custodian.to_account_info(),
-            event_rent_payer: ctx.accounts.owner.to_account_info(),
+            event_rent_payer: ctx.accounts.payer.to_account_info(),
             sender_authority_pda: ctx.accounts.token_messenger_minter_sender_authority.to_account_info(),
             burn_token_account: ctx.accounts.burn_token_account.to_account_info(),
             denylist_account: ctx.accounts.denylist_account.to_account_info(),
@@ -96,22 +184,42 @@ pub fn transfer_v2_ix(
         custodian_seeds,
     );
 
-    let args = DepositForBurnParams {
-        amount,
-        destination_domain,
-        mint_recipient: recipient,
-        destination_caller: [0; 32],
-        max_fee,
-        min_finality_threshold,
+    let destination_caller = ctx.accounts.domain_config.destination_caller(destination_caller);
+    let data = match hook_data {
+        None => {
+            let args = DepositForBurnParams {
+                amount,
+                destination_domain,
+                mint_recipient: recipient,
+                destination_caller,
+                max_fee,
+                min_finality_threshold,
+            };
+
+            const ANCHOR_IX_SELECTOR: [u8; 8] = [215, 60, 61, 46, 114, 55, 128, 176];
+            (ANCHOR_IX_SELECTOR, args).try_to_vec()?
+        }
+        Some(hook_data) => {
+            let args = DepositForBurnWithHookParams {
+                amount,
+                destination_domain,
+                mint_recipient: recipient,
+                destination_caller,
+                max_fee,
+                min_finality_threshold,
+                hook_data,
+            };
+
+            const ANCHOR_IX_SELECTOR: [u8; 8] = [111, 245, 62, 131, 204, 108, 223, 155];
+            (ANCHOR_IX_SELECTOR, args).try_to_vec()?
+        }
     };
 
-    const ANCHOR_IX_SELECTOR: [u8; 8] = [215, 60, 61, 46, 114, 55, 128, 176];
-
     solana_program::program::invoke_signed(
         &solana_program::instruction::Instruction {
             program_id: TOKEN_MESSENGER_MINTER_V2_PROGRAM_ID,
             accounts: cpi_ctx.to_account_metas(None),
-            data: (ANCHOR_IX_SELECTOR, args).try_to_vec()?,
+            data,
         },
         &cpi_ctx.to_account_infos(),
         cpi_ctx.signer_seeds,
@@ -126,17 +234,20 @@ pub fn transfer_v2_ix(
         solana_owner,
         user: ctx.accounts.owner.key(),
         amount,
-        gas_drop_amount,
+        gas_drop_amount: gas_drop,
         cctp_nonce: -2,
         fee_is_native,
         cctp_message: ctx.accounts.message_sent_event_data.key(),
+        hook_data_hash,
+        relayer: if ctx.accounts.owner.is_signer { Pubkey::default() } else { ctx.accounts.payer.key() },
+        partner: params.partner,
     });
 
-    token::close_account(CpiContext::new_with_signer(
+    token_interface::close_account(CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
-        token::CloseAccount {
+        token_interface::CloseAccount {
             account: ctx.accounts.burn_token_account.to_account_info(),
-            destination: ctx.accounts.owner.to_account_info(),
+            destination: ctx.accounts.payer.to_account_info(),
             authority: ctx.accounts.custodian.to_account_info(),
         },
         custodian_seeds,
@@ -150,29 +261,60 @@ pub struct TransferV2Context<'info> {
     #[account(mut, seeds=[b"config"], bump)]
     pub config: Box<Account<'info, Config>>,
 
+    /// Seeds must be \["domain_config", destination_domain\].
+    #[account(
+        mut,
+        seeds = [DomainConfig::SEED_PREFIX, &destination_domain.to_le_bytes()],
+        bump = domain_config.bump,
+    )]
+    pub domain_config: Box<Account<'info, DomainConfig>>,
+
+    /// Seeds must be \["rate_limit"\].
+    #[account(
+        mut,
+        seeds = [RateLimit::SEED_PREFIX],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Box<Account<'info, RateLimit>>,
+
     // Sender ATA
-    #[account(mut, token::mint = burn_token_mint)]
-    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = burn_token_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     // CCTP Message account
     #[account(mut)]
     message_sent_event_data: Signer<'info>,
 
     // Sender
+    /// CHECK: Sender. Either signs the transaction, or has approved `payer` as delegate of
+    /// `owner_token_account` for a relayed transfer.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Pays rent for the transfer accounts. A relayer when `owner` does not sign.
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub payer: Signer<'info>,
 
+    /// Seeds must be \["fee_vault"\].
     #[account(
         mut,
-        address = config.fee_collector_sol,
+        seeds = [FeeVault::SEED_PREFIX],
+        bump,
     )]
-    pub fee_collector_sol_account: SystemAccount<'info>,
+    pub fee_vault: SystemAccount<'info>,
 
+    /// Seeds must be \["fee_vault_token"\].
     #[account(
         mut,
-        address = config.fee_collector_usdc,
+        seeds = [FeeVault::TOKEN_SEED_PREFIX],
+        bump,
     )]
-    pub fee_collector_usdc_account: Box<Account<'info, TokenAccount>>,
+    pub fee_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -184,9 +326,52 @@ pub struct TransferV2Context<'info> {
         mut,
         address = config.gas_drop_collector_usdc,
     )]
-    pub gas_drop_collector_usdc_account: Box<Account<'info, TokenAccount>>,
+    pub gas_drop_collector_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Relayer USDC account receiving the static fee of a relayed transfer.
+    #[account(
+        mut,
+        token::mint = burn_token_mint,
+        token::authority = payer,
+        token::token_program = token_program,
+    )]
+    pub relayer_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Partner sharing the percentage fee.
+    ///
+    /// Seeds must be \["partner", partner\].
+    #[account(
+        seeds = [Partner::SEED_PREFIX, partner.partner.as_ref()],
+        bump = partner.bump,
+    )]
+    pub partner: Option<Box<Account<'info, Partner>>>,
+
+    /// Partner USDC account, must be the partner's payout account.
+    #[account(mut)]
+    pub partner_payout_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Must equal `config.gas_drop_oracle`, required for native gas drops while it is set.
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Denylist entry of `owner`, the transfer is rejected when it exists.
+    ///
+    /// Seeds must be \["blocked_sender", owner\].
+    #[account(
+        seeds = [BlockedSender::SEED_PREFIX, owner.key().as_ref()],
+        bump,
+    )]
+    pub blocked_sender: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Allowlist entry of `owner`, must exist while `config.allowlist_only` is on.
+    ///
+    /// Seeds must be \["allowed_sender", owner\].
+    #[account(
+        seeds = [AllowedSender::SEED_PREFIX, owner.key().as_ref()],
+        bump,
+    )]
+    pub allowed_sender: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 
     /// This program's emitter authority.
@@ -200,13 +385,14 @@ pub struct TransferV2Context<'info> {
 
     /// Circle-supported mint.
     ///
-    /// CHECK: Mutable. This token account's mint must be the same as the one found in the CCTP
-    /// Token Messenger Minter program's local token account.
+    /// Mutable. This token account's mint must be the same as the one found in the CCTP Token
+    /// Messenger Minter program's local token account. Its owner selects the token program.
     #[account(
         mut,
         address = USDC_MINT,
+        constraint = burn_token_mint.to_account_info().owner == token_program.key @ TransferError::InvalidTokenProgram,
     )]
-    burn_token_mint: AccountInfo<'info>,
+    burn_token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// Temporary custody token account. This account will be closed at the end of this instruction.
     /// It just acts as a conduit to allow this program to be the transfer initiator in the CCTP
@@ -215,13 +401,21 @@ pub struct TransferV2Context<'info> {
     /// Seeds must be \["__custody"\].
     #[account(
         init,
-        payer = owner,
+        payer = payer,
         token::mint = burn_token_mint,
         token::authority = custodian,
+        token::token_program = token_program,
         seeds = [Custodian::ATA_SEED_PREFIX],
         bump,
     )]
-    burn_token_account: Box<Account<'info, token::TokenAccount>>,
+    burn_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Mutable. Marks the signed quote as consumed, created by the transfer. The transfer
+    /// fails if the quote was already used.
+    ///
+    /// Seeds must be \["used_quote", sha256(signed quote message)\].
+    #[account(mut)]
+    pub used_quote: UncheckedAccount<'info>,
     
     /// CHECK: denylist PDA
     /// Account is denylisted if the account exists at the expected PDA.
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::errors::TransferError;
+use super::{
+    process_transfer_v2,
+    TransferV2Context,
+};
+
+/// Upper bound on the CCTP v2 hook data accepted by `transfer_v2_with_hook`.
+pub const MAX_HOOK_DATA_LEN: usize = 256;
+
+pub fn transfer_v2_with_hook_ix(
+    ctx: Context<TransferV2Context>,
+    usdc_amount: u64,
+    destination_domain: u32,
+    recipient: [u8; 32],
+    solana_owner: [u8; 32],
+    fee: u64,
+    deadline: u64,
+    gas_drop_amount: u64,
+    fee_is_native: bool,
+    max_fee: u64,
+    min_finality_threshold: u32,
+    quote_id: u64,
+    destination_caller: [u8; 32],
+    hook_data: Vec<u8>,
+    ed25519_ix_index: u16,
+) -> Result<()> {
+    require!(
+        !hook_data.is_empty() && hook_data.len() <= MAX_HOOK_DATA_LEN,
+        TransferError::InvalidHookData,
+    );
+    process_transfer_v2(
+        ctx,
+        usdc_amount,
+        destination_domain,
+        recipient,
+        solana_owner,
+        fee,
+        deadline,
+        gas_drop_amount,
+        fee_is_native,
+        max_fee,
+        min_finality_threshold,
+        quote_id,
+        destination_caller,
+        Some(hook_data),
+        ed25519_ix_index,
+    )
+}
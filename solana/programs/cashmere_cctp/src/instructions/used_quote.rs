@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::{
+    state::UsedQuote,
+    errors::TransferError,
+};
+
+/// Closes the marker of a consumed quote once its deadline has passed and returns the rent to the
+/// account that paid for it. Once expired the quote is rejected by the deadline check anyway, so
+/// anyone may call this.
+pub fn close_used_quote_ix(ctx: Context<CloseUsedQuoteContext>, _quote_hash: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp as u64 > ctx.accounts.used_quote.deadline, TransferError::QuoteNotExpired);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(quote_hash: [u8; 32])]
+pub struct CloseUsedQuoteContext<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        has_one = rent_payer,
+        seeds = [UsedQuote::SEED_PREFIX, &quote_hash],
+        bump = used_quote.bump,
+    )]
+    pub used_quote: Account<'info, UsedQuote>,
+
+    #[account(mut)]
+    pub rent_payer: SystemAccount<'info>,
+}
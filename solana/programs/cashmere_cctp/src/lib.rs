@@ -1,3 +1,5 @@
+#![allow(clippy::too_many_arguments)]
+
 pub mod cctp;
 pub mod state;
 pub mod errors;
@@ -7,9 +9,30 @@ pub mod utils;
 
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::{ConfigChange, DomainConfigParams, Lane, Role, SenderList};
+use utils::FeeBreakdown;
+
+#[cfg(not(any(feature = "mainnet", feature = "devnet", feature = "localnet")))]
+compile_error!("one of the `mainnet`, `devnet` or `localnet` features must be enabled");
+
+#[cfg(any(
+    all(feature = "mainnet", feature = "devnet"),
+    all(feature = "mainnet", feature = "localnet"),
+    all(feature = "devnet", feature = "localnet"),
+))]
+compile_error!("only one of the `mainnet`, `devnet` or `localnet` features can be enabled");
+
+// the program is not deployed on mainnet yet, set its ID here once it is
+#[cfg(feature = "mainnet")]
+compile_error!("no mainnet program ID yet, the `mainnet` feature cannot be built");
 
+#[cfg(feature = "devnet")]
 declare_id!("5RsvKL6LFq6yEFiAXEwgYHAN3aLFypeB4AaafdeDnHqM");
 
+// local validator only, the program is loaded at genesis under this address
+#[cfg(feature = "localnet")]
+declare_id!("7H9fxar99SYCkub2WaMsHdxbM6DPRFsYa1h2bJQpJGH9");
+
 #[program]
 pub mod cashmere_cctp {
     use super::*;
@@ -18,12 +41,16 @@ pub mod cashmere_cctp {
 
     pub fn initialize(
         ctx: Context<InitializeContext>,
-        fee_collector_sol: Pubkey,
-        fee_collector_usdc: Pubkey,
         gas_drop_collector_sol: Pubkey,
         gas_drop_collector_usdc: Pubkey,
     ) -> Result<()> {
-        initialize_ix(ctx, fee_collector_sol, fee_collector_usdc, gas_drop_collector_sol, gas_drop_collector_usdc)
+        initialize_ix(ctx, gas_drop_collector_sol, gas_drop_collector_usdc)
+    }
+
+    // migrate config ix
+
+    pub fn migrate_config(ctx: Context<MigrateConfigContext>) -> Result<()> {
+        migrate_config_ix(ctx)
     }
 
     // admin ixs
@@ -32,38 +59,155 @@ pub mod cashmere_cctp {
         set_paused_ix(ctx, paused)
     }
 
-    pub fn set_fee_bp(ctx: Context<ConfigContext>, fee_bp: u64) -> Result<()> {
-        set_fee_bp_ix(ctx, fee_bp)
+    pub fn set_lane_paused(ctx: Context<ConfigContext>, lane: Lane, paused: bool) -> Result<()> {
+        set_lane_paused_ix(ctx, lane, paused)
     }
 
-    pub fn set_signer_key(ctx: Context<ConfigContext>, signer_key: [u8; 32]) -> Result<()> {
-        set_signer_key_ix(ctx, signer_key)
+    pub fn set_max_usdc_gas_drop(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+        set_max_usdc_gas_drop_ix(ctx, max_gas)
     }
 
-    pub fn set_fee_collector(ctx: Context<ConfigContext>, fee_collector_sol: Pubkey, fee_collector_usdc: Pubkey) -> Result<()> {
-        set_fee_collector_ix(ctx, fee_collector_sol, fee_collector_usdc)
+    pub fn set_max_native_gas_drop(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+        set_max_native_gas_drop_ix(ctx, max_gas)
     }
 
-    pub fn set_gas_drop_collector(ctx: Context<ConfigContext>, gas_drop_collector_sol: Pubkey, gas_drop_collector_usdc: Pubkey) -> Result<()> {
-        set_gas_drop_collector_ix(ctx, gas_drop_collector_sol, gas_drop_collector_usdc)
+    pub fn set_max_receive_gas_drop(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
+        set_max_receive_gas_drop_ix(ctx, max_gas)
     }
 
-    pub fn set_max_usdc_gas_drop(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
-        set_max_usdc_gas_drop_ix(ctx, max_gas)
+    pub fn withdraw_gas_vault(ctx: Context<WithdrawGasVaultContext>, amount: u64) -> Result<()> {
+        withdraw_gas_vault_ix(ctx, amount)
     }
 
-    pub fn set_max_native_gas_drop(ctx: Context<ConfigContext>, max_gas: u64) -> Result<()> {
-        set_max_native_gas_drop_ix(ctx, max_gas)
+    pub fn set_transfer_amount_limits(ctx: Context<ConfigContext>, min_transfer_amount: u64, max_transfer_amount: u64) -> Result<()> {
+        set_transfer_amount_limits_ix(ctx, min_transfer_amount, max_transfer_amount)
+    }
+
+    pub fn set_gas_drop_oracle(ctx: Context<ConfigContext>, oracle: Pubkey, max_price_age: u64, max_confidence_bp: u64) -> Result<()> {
+        set_gas_drop_oracle_ix(ctx, oracle, max_price_age, max_confidence_bp)
+    }
+
+    pub fn set_require_full_quote(ctx: Context<ConfigContext>, require_full_quote: bool) -> Result<()> {
+        set_require_full_quote_ix(ctx, require_full_quote)
+    }
+
+    pub fn set_allowlist_only(ctx: Context<ConfigContext>, allowlist_only: bool) -> Result<()> {
+        set_allowlist_only_ix(ctx, allowlist_only)
+    }
+
+    pub fn grant_role(ctx: Context<RoleContext>, role: Role, holder: Pubkey) -> Result<()> {
+        grant_role_ix(ctx, role, holder)
+    }
+
+    pub fn revoke_role(ctx: Context<RoleContext>, role: Role) -> Result<()> {
+        revoke_role_ix(ctx, role)
+    }
+
+    pub fn propose_owner(ctx: Context<TransferOwnershipContext>, new_owner: Pubkey) -> Result<()> {
+        propose_owner_ix(ctx, new_owner)
+    }
+
+    pub fn cancel_ownership_proposal(ctx: Context<TransferOwnershipContext>) -> Result<()> {
+        cancel_ownership_proposal_ix(ctx)
+    }
+
+    pub fn accept_ownership(ctx: Context<AcceptOwnershipContext>) -> Result<()> {
+        accept_ownership_ix(ctx)
+    }
+
+    // timelock ixs
+
+    pub fn queue_config_change(ctx: Context<QueueConfigChangeContext>, change: ConfigChange) -> Result<()> {
+        queue_config_change_ix(ctx, change)
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChangeContext>, id: u64) -> Result<()> {
+        execute_config_change_ix(ctx, id)
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChangeContext>, id: u64) -> Result<()> {
+        cancel_config_change_ix(ctx, id)
+    }
+
+    // fee vault ixs
+
+    pub fn initialize_fee_vault(ctx: Context<InitializeFeeVaultContext>) -> Result<()> {
+        initialize_fee_vault_ix(ctx)
+    }
+
+    pub fn withdraw_fee(ctx: Context<WithdrawFeeContext>, usdc_amount: u64, native_amount: u64) -> Result<()> {
+        withdraw_fee_ix(ctx, usdc_amount, native_amount)
+    }
+
+    // rate limit ixs
+
+    pub fn initialize_rate_limit(ctx: Context<InitializeRateLimitContext>) -> Result<()> {
+        initialize_rate_limit_ix(ctx)
+    }
+
+    pub fn set_rate_limit(ctx: Context<RateLimitContext>, capacity: u64) -> Result<()> {
+        set_rate_limit_ix(ctx, capacity)
+    }
+
+    // sender list ixs
+
+    pub fn add_senders<'info>(ctx: Context<'_, '_, 'info, 'info, SenderListContext<'info>>, list: SenderList, senders: Vec<Pubkey>) -> Result<()> {
+        add_senders_ix(ctx, list, senders)
+    }
+
+    pub fn remove_senders<'info>(ctx: Context<'_, '_, 'info, 'info, SenderListContext<'info>>, list: SenderList, senders: Vec<Pubkey>) -> Result<()> {
+        remove_senders_ix(ctx, list, senders)
+    }
+
+    // partner ixs
+
+    pub fn register_partner(ctx: Context<RegisterPartnerContext>, partner_key: Pubkey) -> Result<()> {
+        register_partner_ix(ctx, partner_key)
     }
 
-    pub fn transfer_ownership(ctx: Context<TransferOwnershipContext>, new_owner: Pubkey) -> Result<()> {
-        transfer_ownership_ix(ctx, new_owner)
+    pub fn remove_partner(ctx: Context<RemovePartnerContext>, partner_key: Pubkey) -> Result<()> {
+        remove_partner_ix(ctx, partner_key)
+    }
+
+    // domain config ixs
+
+    pub fn create_domain_config(ctx: Context<CreateDomainConfigContext>, domain: u32) -> Result<()> {
+        create_domain_config_ix(ctx, domain)
+    }
+
+    pub fn update_domain_config(ctx: Context<DomainConfigContext>, domain: u32, params: DomainConfigParams) -> Result<()> {
+        update_domain_config_ix(ctx, domain, params)
+    }
+
+    pub fn set_domain_enabled(ctx: Context<DomainConfigContext>, domain: u32, enabled: bool) -> Result<()> {
+        set_domain_enabled_ix(ctx, domain, enabled)
     }
 
     // get fee ix
 
-    pub fn get_fee(ctx: Context<GetFeeContext>, fee: u64, amount: u64) -> Result<u64> {
-        get_fee_ix(ctx, fee, amount)
+    pub fn get_fee(
+        ctx: Context<GetFeeContext>,
+        destination_domain: u32,
+        fee: u64,
+        amount: u64,
+        gas_drop_amount: u64,
+        fee_is_native: bool,
+    ) -> Result<FeeBreakdown> {
+        get_fee_ix(ctx, destination_domain, fee, amount, gas_drop_amount, fee_is_native)
+    }
+
+    // quote ix
+
+    pub fn quote(
+        ctx: Context<QuoteContext>,
+        usdc_amount: u64,
+        destination_domain: u32,
+        cctp_version: u8,
+        fee: u64,
+        gas_drop_amount: u64,
+        fee_is_native: bool,
+    ) -> Result<QuoteResult> {
+        quote_ix(ctx, usdc_amount, destination_domain, cctp_version, fee, gas_drop_amount, fee_is_native)
     }
 
     // transfer
@@ -78,6 +222,9 @@ pub mod cashmere_cctp {
         deadline: u64,
         gas_drop_amount: u64,
         fee_is_native: bool,
+        quote_id: u64,
+        destination_caller: [u8; 32],
+        ed25519_ix_index: u16,
     ) -> Result<()> {
         transfer_ix(
             ctx,
@@ -89,6 +236,9 @@ pub mod cashmere_cctp {
             deadline,
             gas_drop_amount,
             fee_is_native,
+            quote_id,
+            destination_caller,
+            ed25519_ix_index,
         )
     }
 
@@ -104,6 +254,9 @@ pub mod cashmere_cctp {
         fee_is_native: bool,
         max_fee: u64,
         min_finality_threshold: u32,
+        quote_id: u64,
+        destination_caller: [u8; 32],
+        ed25519_ix_index: u16,
     ) -> Result<()> {
         transfer_v2_ix(
             ctx,
@@ -117,8 +270,77 @@ pub mod cashmere_cctp {
             fee_is_native,
             max_fee,
             min_finality_threshold,
+            quote_id,
+            destination_caller,
+            ed25519_ix_index,
         )
     }
+
+    pub fn transfer_v2_with_hook(
+        ctx: Context<TransferV2Context>,
+        usdc_amount: u64,
+        destination_domain: u32,
+        recipient: [u8; 32],
+        solana_owner: [u8; 32],
+        fee: u64,
+        deadline: u64,
+        gas_drop_amount: u64,
+        fee_is_native: bool,
+        max_fee: u64,
+        min_finality_threshold: u32,
+        quote_id: u64,
+        destination_caller: [u8; 32],
+        hook_data: Vec<u8>,
+        ed25519_ix_index: u16,
+    ) -> Result<()> {
+        transfer_v2_with_hook_ix(
+            ctx,
+            usdc_amount,
+            destination_domain,
+            recipient,
+            solana_owner,
+            fee,
+            deadline,
+            gas_drop_amount,
+            fee_is_native,
+            max_fee,
+            min_finality_threshold,
+            quote_id,
+            destination_caller,
+            hook_data,
+            ed25519_ix_index,
+        )
+    }
+
+    // receive
+
+    pub fn receive(
+        ctx: Context<ReceiveContext>,
+        message: Vec<u8>,
+        attestation: Vec<u8>,
+        source_nonce: u64,
+        gas_drop_amount: u64,
+        ed25519_ix_index: u16,
+    ) -> Result<()> {
+        receive_ix(ctx, message, attestation, source_nonce, gas_drop_amount, ed25519_ix_index)
+    }
+
+    pub fn receive_v2(
+        ctx: Context<ReceiveV2Context>,
+        message: Vec<u8>,
+        attestation: Vec<u8>,
+        source_nonce: u64,
+        gas_drop_amount: u64,
+        ed25519_ix_index: u16,
+    ) -> Result<()> {
+        receive_v2_ix(ctx, message, attestation, source_nonce, gas_drop_amount, ed25519_ix_index)
+    }
+
+    // used quote registry
+
+    pub fn close_used_quote(ctx: Context<CloseUsedQuoteContext>, quote_hash: [u8; 32]) -> Result<()> {
+        close_used_quote_ix(ctx, quote_hash)
+    }
 }
 
 
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use bytemuck::{Pod, Zeroable};
+use crate::utils::calculate_fee;
 
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 #[repr(C)]
@@ -16,6 +17,45 @@ pub struct Ed25519SignatureOffsets {
 #[account]
 #[derive(Debug, InitSpace)]
 pub struct Config {
+    pub version: u8, // layout version, first so that later migrations can read it, see `migrate_config`
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey, // proposed owner, default when there is no proposal
+    pub pauser: Pubkey,
+    pub fee_manager: Pubkey,
+    pub signer_manager: Pubkey,
+    pub treasury: Pubkey,
+    pub compliance: Pubkey,
+    pub gas_drop_collector_sol: Pubkey,
+    pub gas_drop_collector_usdc: Pubkey,
+    pub nonce: u64,
+    pub signer_key: [u8; 32],
+    pub previous_signer_key: [u8; 32], // outgoing signer key, accepted until its expiry
+    pub previous_signer_key_expiry: u64, // unix timestamp
+    pub signer_set: [[u8; 32]; Config::MAX_SIGNERS], // first `signer_set_len` entries are in use
+    pub signer_set_len: u8,
+    pub signer_threshold: u8, // signatures required from the signer set, 0 uses `signer_key` instead
+    pub max_usdc_gas_drop: u64, // cap over every domain, in micro-USDC (default 100m)
+    pub max_native_gas_drop: u64, // cap over every domain
+    pub max_receive_gas_drop: u64, // in lamports, delivered on inbound transfers
+    pub min_transfer_amount: u64, // in micro-USDC
+    pub max_transfer_amount: u64, // in micro-USDC, 0 for no cap
+    pub gas_drop_oracle: Pubkey, // Pyth SOL/USD `PriceUpdateV2` account pricing native gas drops, default when off
+    pub max_oracle_price_age: u64, // in seconds
+    pub max_oracle_confidence_bp: u64,
+    pub paused: bool,
+    pub paused_lanes: u8, // bitmask of paused [Lane]s
+    pub require_full_quote: bool, // reject quotes signed in the legacy format, off on migrated configs
+    pub allowlist_only: bool, // only senders with an `AllowedSender` entry may transfer
+    pub timelock_delay: u64, // in seconds, minimum wait between queueing and executing a `ConfigChange`
+    pub next_change_id: u64, // id of the next queued `PendingChange`
+    pub default_fee_bp: u64, // percentage fee of newly created domains, the legacy global `fee_bp`
+    pub reserved: [u8; Config::RESERVED_LEN], // zeroed space for future fields
+}
+
+/// Layout of [Config] before versioning, frozen to read and migrate deployed accounts. It was
+/// allocated with `size_of::<ConfigV0>() + 8` bytes.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ConfigV0 {
     pub owner: Pubkey,
     pub fee_collector_sol: Pubkey,
     pub fee_collector_usdc: Pubkey,
@@ -24,11 +64,372 @@ pub struct Config {
     pub fee_bp: u64,
     pub nonce: u64,
     pub signer_key: [u8; 32],
-    pub max_usdc_gas_drop: u64, // in micro-USDC (default 100m)
+    pub max_usdc_gas_drop: u64,
     pub max_native_gas_drop: u64,
     pub paused: bool,
 }
 
+impl ConfigV0 {
+    pub const ACCOUNT_LEN: usize = 240;
+
+    /// Current config carrying over the owner, collectors, nonce, signer key, gas drop limits and
+    /// pause flag. `fee_bp` becomes the fee of the domains created afterwards. The fee collectors
+    /// have no counterpart anymore, fees now go to the fee vault, they are dropped. New fields start
+    /// at their defaults, legacy quotes stay accepted so that signers can move to the full quote
+    /// after the upgrade.
+    pub fn migrate(&self) -> Config {
+        Config {
+            version: Config::VERSION,
+            owner: self.owner,
+            pending_owner: Pubkey::default(),
+            pauser: Pubkey::default(),
+            fee_manager: Pubkey::default(),
+            signer_manager: Pubkey::default(),
+            treasury: Pubkey::default(),
+            compliance: Pubkey::default(),
+            gas_drop_collector_sol: self.gas_drop_collector_sol,
+            gas_drop_collector_usdc: self.gas_drop_collector_usdc,
+            nonce: self.nonce,
+            signer_key: self.signer_key,
+            previous_signer_key: [0; 32],
+            previous_signer_key_expiry: 0,
+            signer_set: [[0; 32]; Config::MAX_SIGNERS],
+            signer_set_len: 0,
+            signer_threshold: 0,
+            max_usdc_gas_drop: self.max_usdc_gas_drop,
+            max_native_gas_drop: self.max_native_gas_drop,
+            max_receive_gas_drop: 0,
+            min_transfer_amount: 0,
+            max_transfer_amount: 0,
+            gas_drop_oracle: Pubkey::default(),
+            max_oracle_price_age: 0,
+            max_oracle_confidence_bp: 0,
+            paused: self.paused,
+            paused_lanes: 0,
+            require_full_quote: false,
+            allowlist_only: false,
+            timelock_delay: Config::DEFAULT_TIMELOCK_DELAY,
+            next_change_id: 0,
+            default_fee_bp: self.fee_bp,
+            reserved: [0; Config::RESERVED_LEN],
+        }
+    }
+}
+
+/// Admin roles. Each role is held by a single key stored in [Config], the owner holds all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum Role {
+    Pauser,
+    FeeManager,
+    SignerManager,
+    Treasury,
+    Compliance,
+}
+
+impl Config {
+    pub const MAX_SIGNERS: usize = 8;
+    pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+    pub const MAX_SIGNER_KEY_OVERLAP: u64 = 24 * 60 * 60;
+    pub const DEFAULT_TIMELOCK_DELAY: u64 = 24 * 60 * 60;
+    pub const VERSION: u8 = 1;
+    pub const RESERVED_LEN: usize = 120;
+
+    pub fn signer_set(&self) -> &[[u8; 32]] {
+        &self.signer_set[..self.signer_set_len as usize]
+    }
+
+    pub fn role_holder(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Pauser => self.pauser,
+            Role::FeeManager => self.fee_manager,
+            Role::SignerManager => self.signer_manager,
+            Role::Treasury => self.treasury,
+            Role::Compliance => self.compliance,
+        }
+    }
+
+    pub fn set_role_holder(&mut self, role: Role, holder: Pubkey) {
+        match role {
+            Role::Pauser => self.pauser = holder,
+            Role::FeeManager => self.fee_manager = holder,
+            Role::SignerManager => self.signer_manager = holder,
+            Role::Treasury => self.treasury = holder,
+            Role::Compliance => self.compliance = holder,
+        }
+    }
+
+    /// Keys whose signatures are currently accepted: the signer key, plus the previous signer key
+    /// while its rotation overlap window is open.
+    pub fn signer_keys(&self, now: u64) -> Vec<[u8; 32]> {
+        let mut keys = vec![self.signer_key];
+        if now <= self.previous_signer_key_expiry {
+            keys.push(self.previous_signer_key);
+        }
+        keys
+    }
+
+    /// Whether native gas drops are signed in micro-USDC and priced with `gas_drop_oracle`, rather
+    /// than signed in lamports.
+    pub fn gas_drop_priced(&self) -> bool {
+        self.gas_drop_oracle != Pubkey::default()
+    }
+
+    pub fn lane_paused(&self, lane: Lane) -> bool {
+        self.paused_lanes & lane.bit() != 0
+    }
+
+    pub fn set_lane_paused(&mut self, lane: Lane, paused: bool) {
+        if paused {
+            self.paused_lanes |= lane.bit();
+        } else {
+            self.paused_lanes &= !lane.bit();
+        }
+    }
+
+    pub fn has_role(&self, key: &Pubkey, role: Role) -> bool {
+        *key == self.owner || *key == self.role_holder(role)
+    }
+}
+
+/// Per destination domain settings. A transfer to a domain requires its `DomainConfig` to exist
+/// and be enabled.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct DomainConfig {
+    pub domain: u32,
+    pub enabled: bool,
+    pub fee_bp: u64,
+    pub min_fee: u64, // floor of the static fee when paid in USDC, in micro-USDC
+    pub fee_tiers: [FeeTier; DomainConfig::MAX_FEE_TIERS], // first `fee_tier_count` entries are in use
+    pub fee_tier_count: u8,
+    pub min_percentage_fee: u64, // in micro-USDC
+    pub max_percentage_fee: u64, // in micro-USDC, 0 for no cap
+    pub rate_limit: RateLimitBucket, // outflow limit towards this domain
+    pub max_usdc_gas_drop: u64,
+    pub max_native_gas_drop: u64,
+    pub cctp_versions: u8, // bitmask of allowed CCTP versions, bit 0 is v1
+    pub destination_caller: [u8; 32], // default CCTP destination caller, zero lets anyone redeem
+    pub bump: u8,
+}
+
+impl DomainConfig {
+    pub const SEED_PREFIX: &'static [u8] = b"domain_config";
+    pub const ALL_CCTP_VERSIONS: u8 = 0b11;
+    pub const MAX_FEE_TIERS: usize = 4;
+
+    pub fn allows_cctp_version(&self, cctp_version: u8) -> bool {
+        (1..=8).contains(&cctp_version) && self.cctp_versions & (1 << (cctp_version - 1)) != 0
+    }
+
+    pub fn fee_tiers(&self) -> &[FeeTier] {
+        &self.fee_tiers[..self.fee_tier_count as usize]
+    }
+
+    /// Percentage fee on `amount`, at the rate of the highest tier it reaches (`fee_bp` below the
+    /// first tier), clamped to the minimum and maximum percentage fee.
+    pub fn percentage_fee(&self, amount: u64) -> u64 {
+        let fee_bp = self.fee_tiers().iter()
+            .rev()
+            .find(|tier| amount >= tier.min_amount)
+            .map_or(self.fee_bp, |tier| tier.fee_bp);
+        let fee = calculate_fee(amount, fee_bp, 0).max(self.min_percentage_fee);
+        if self.max_percentage_fee > 0 {
+            fee.min(self.max_percentage_fee)
+        } else {
+            fee
+        }
+    }
+
+    pub fn fees(&self) -> DomainFees {
+        DomainFees {
+            fee_bp: self.fee_bp,
+            min_fee: self.min_fee,
+            fee_tiers: self.fee_tiers().to_vec(),
+            min_percentage_fee: self.min_percentage_fee,
+            max_percentage_fee: self.max_percentage_fee,
+        }
+    }
+
+    pub fn set_fees(&mut self, fees: &DomainFees) {
+        self.fee_bp = fees.fee_bp;
+        self.min_fee = fees.min_fee;
+        self.fee_tiers = [FeeTier::default(); DomainConfig::MAX_FEE_TIERS];
+        self.fee_tiers[..fees.fee_tiers.len()].copy_from_slice(&fees.fee_tiers);
+        self.fee_tier_count = fees.fee_tiers.len() as u8;
+        self.min_percentage_fee = fees.min_percentage_fee;
+        self.max_percentage_fee = fees.max_percentage_fee;
+    }
+
+    pub fn params(&self) -> DomainConfigParams {
+        DomainConfigParams {
+            rate_limit_capacity: self.rate_limit.capacity,
+            max_usdc_gas_drop: self.max_usdc_gas_drop,
+            max_native_gas_drop: self.max_native_gas_drop,
+            cctp_versions: self.cctp_versions,
+            destination_caller: self.destination_caller,
+        }
+    }
+
+    /// Destination caller of a transfer, `caller_override` wins unless it is zero.
+    pub fn destination_caller(&self, caller_override: [u8; 32]) -> [u8; 32] {
+        if caller_override == [0; 32] {
+            self.destination_caller
+        } else {
+            caller_override
+        }
+    }
+}
+
+/// Token bucket limiting the USDC burned over a rolling window. It holds up to `capacity` and
+/// refills linearly, by `capacity` per [RateLimitBucket::WINDOW]. A zero capacity disables it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct RateLimitBucket {
+    pub capacity: u64,
+    pub available: u64,
+    pub last_refill: i64, // unix timestamp
+}
+
+impl RateLimitBucket {
+    pub const WINDOW: i64 = 24 * 60 * 60;
+
+    /// Refills the bucket up to `now` and takes `amount` out of it. Returns false, leaving the
+    /// bucket untouched, when `amount` exceeds what is available.
+    pub fn consume(&mut self, amount: u64, now: i64) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+        let elapsed = now.saturating_sub(self.last_refill).max(0) as u128;
+        let refill = self.capacity as u128 * elapsed / Self::WINDOW as u128;
+        let available = (self.available as u128 + refill).min(self.capacity as u128) as u64;
+        if amount > available {
+            return false;
+        }
+        self.available = available - amount;
+        self.last_refill = now;
+        true
+    }
+
+    /// Sets the capacity and starts over with a full bucket.
+    pub fn set_capacity(&mut self, capacity: u64, now: i64) {
+        self.capacity = capacity;
+        self.available = capacity;
+        self.last_refill = now;
+    }
+}
+
+/// Global outflow limit, applied on top of the per-domain ones.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct RateLimit {
+    pub bucket: RateLimitBucket,
+    pub bump: u8,
+}
+
+impl RateLimit {
+    pub const SEED_PREFIX: &'static [u8] = b"rate_limit";
+}
+
+/// Part of the bridge that can be paused on its own, see `set_lane_paused`. `Config::paused`
+/// stops all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum Lane {
+    CctpV1,
+    CctpV2,
+    NativeFee,
+    UsdcFee,
+    GasDrop,
+}
+
+impl Lane {
+    pub fn bit(&self) -> u8 {
+        1 << *self as u8
+    }
+
+    pub fn cctp(cctp_version: u8) -> Option<Lane> {
+        match cctp_version {
+            1 => Some(Lane::CctpV1),
+            2 => Some(Lane::CctpV2),
+            _ => None,
+        }
+    }
+
+    pub fn fee(fee_is_native: bool) -> Lane {
+        if fee_is_native {
+            Lane::NativeFee
+        } else {
+            Lane::UsdcFee
+        }
+    }
+}
+
+/// Denylist entry, transfers from `sender` are rejected while it exists.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct BlockedSender {
+    pub sender: Pubkey,
+    pub bump: u8,
+}
+
+impl BlockedSender {
+    pub const SEED_PREFIX: &'static [u8] = b"blocked_sender";
+}
+
+/// Allowlist entry, required for `sender` to transfer while `Config::allowlist_only` is on.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct AllowedSender {
+    pub sender: Pubkey,
+    pub bump: u8,
+}
+
+impl AllowedSender {
+    pub const SEED_PREFIX: &'static [u8] = b"allowed_sender";
+}
+
+/// Sender list managed by `add_senders` and `remove_senders`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum SenderList {
+    Blocked,
+    Allowed,
+}
+
+impl SenderList {
+    pub fn seed_prefix(&self) -> &'static [u8] {
+        match self {
+            SenderList::Blocked => BlockedSender::SEED_PREFIX,
+            SenderList::Allowed => AllowedSender::SEED_PREFIX,
+        }
+    }
+}
+
+/// Volume tier of the percentage fee, applies to transfers of at least `min_amount` micro-USDC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct FeeTier {
+    pub min_amount: u64,
+    pub fee_bp: u64,
+}
+
+/// Fee fields of a [DomainConfig], only changed through the timelock.
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct DomainFees {
+    pub fee_bp: u64,
+    pub min_fee: u64,
+    #[max_len(4)] // DomainConfig::MAX_FEE_TIERS
+    pub fee_tiers: Vec<FeeTier>, // sorted by `min_amount`
+    pub min_percentage_fee: u64,
+    pub max_percentage_fee: u64,
+}
+
+/// Settable fields of a [DomainConfig] other than its fees.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct DomainConfigParams {
+    pub rate_limit_capacity: u64,
+    pub max_usdc_gas_drop: u64,
+    pub max_native_gas_drop: u64,
+    pub cctp_versions: u8,
+    pub destination_caller: [u8; 32],
+}
+
 #[account]
 #[derive(Debug, InitSpace)]
 pub struct Custodian {
@@ -39,3 +440,114 @@ impl Custodian {
     pub const SEED_PREFIX: &'static [u8] = b"emitter";
     pub const ATA_SEED_PREFIX: &'static [u8] = b"__custody";
 }
+
+/// System-owned PDA holding the SOL used for gas drops on inbound transfers. It is funded with
+/// plain lamport transfers.
+pub struct GasVault;
+
+impl GasVault {
+    pub const SEED_PREFIX: &'static [u8] = b"gas_vault";
+}
+
+/// Program-owned fee vaults. Native fees accrue in a system-owned PDA, USDC fees in a token
+/// account PDA owned by the custodian. Both are emptied with `withdraw_fee`.
+pub struct FeeVault;
+
+impl FeeVault {
+    pub const SEED_PREFIX: &'static [u8] = b"fee_vault";
+    pub const TOKEN_SEED_PREFIX: &'static [u8] = b"fee_vault_token";
+}
+
+/// Marker for a consumed signed quote, keyed on the sha256 of the message that was verified. It
+/// exists from the transfer that used the quote until it is closed after the quote's deadline, so
+/// every quote can be used at most once.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct UsedQuote {
+    pub rent_payer: Pubkey,
+    pub deadline: u64,
+    pub bump: u8,
+}
+
+impl UsedQuote {
+    pub const SEED_PREFIX: &'static [u8] = b"used_quote";
+}
+
+/// Sensitive config change, only applied through the timelock: queued by the holder of its role,
+/// executable by anyone once its delay has passed.
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub enum ConfigChange {
+    SignerKey {
+        signer_key: [u8; 32],
+        previous_key_overlap: u64, // seconds the outgoing key stays valid once the change executes
+    },
+    SignerSet {
+        #[max_len(8)] // Config::MAX_SIGNERS
+        signers: Vec<[u8; 32]>,
+        threshold: u8,
+    },
+    GasDropCollector {
+        gas_drop_collector_sol: Pubkey,
+        gas_drop_collector_usdc: Pubkey,
+    },
+    TimelockDelay {
+        timelock_delay: u64,
+    },
+    DomainFees {
+        domain: u32,
+        fees: DomainFees,
+    },
+    RequireFullQuote {
+        require_full_quote: bool,
+    },
+    Partner {
+        partner: Pubkey,
+        share_bp: u64,
+        payout: Pubkey,
+    },
+}
+
+impl ConfigChange {
+    /// Role allowed to queue and cancel the change, `None` when it is reserved to the owner.
+    pub fn role(&self) -> Option<Role> {
+        match self {
+            ConfigChange::SignerKey { .. }
+            | ConfigChange::SignerSet { .. }
+            | ConfigChange::RequireFullQuote { .. } => Some(Role::SignerManager),
+            ConfigChange::GasDropCollector { .. } => Some(Role::Treasury),
+            ConfigChange::TimelockDelay { .. } => None,
+            ConfigChange::DomainFees { .. } | ConfigChange::Partner { .. } => Some(Role::FeeManager),
+        }
+    }
+}
+
+/// Queued [ConfigChange]. It is closed, refunding the proposer, when executed or cancelled.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub proposer: Pubkey,
+    pub eta: u64, // unix timestamp from which the change can be executed
+    pub bump: u8,
+}
+
+impl PendingChange {
+    pub const SEED_PREFIX: &'static [u8] = b"pending_change";
+}
+
+/// Integrator sharing the percentage fee of the transfers it brings in. A transfer names its
+/// partner by passing this account, the signed quote commits to it. It is registered without a
+/// share, `share_bp` and `payout` are set through the timelock with `ConfigChange::Partner`.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct Partner {
+    pub partner: Pubkey,
+    pub share_bp: u64, // share of the percentage fee, in basis points
+    pub payout: Pubkey, // USDC token account receiving the share
+    pub bump: u8,
+}
+
+impl Partner {
+    pub const SEED_PREFIX: &'static [u8] = b"partner";
+}
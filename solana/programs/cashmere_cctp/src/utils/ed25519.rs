@@ -2,92 +2,181 @@ use anchor_lang::{
     prelude::*,
     solana_program::{
         ed25519_program,
+        instruction::Instruction,
         sysvar::instructions as sysvar,
     },
 };
 use crate::{
     errors::SignatureVerificationError,
-    state::Ed25519SignatureOffsets,
+    state::{Config, Ed25519SignatureOffsets},
 };
 
 const PUBKEY_SERIALIZED_SIZE: usize = 32; // Size of a serialized public key
 const SIGNATURE_SERIALIZED_SIZE: usize = 64; // Size of a serialized signature
 const SIGNATURE_OFFSETS_SERIALIZED_SIZE: usize = 14; // Size of serialized signature offsets
-const SIGNATURE_OFFSETS_START: usize = 0; // Starting index for signature offsets in instruction data
+const SIGNATURE_OFFSETS_START: usize = 2; // Starting index for signature offsets in instruction data
+const CURRENT_INSTRUCTION_INDEX: u16 = u16::MAX; // Instruction index referring to the Ed25519 instruction itself
 
-pub fn verify_ed25519_ix(instructions: &AccountInfo, msg: &[u8], pub_key: &[u8]) -> Result<()> {
-    // Fetch the previous instruction relative to the current one
-    let verify_instruction = sysvar::get_instruction_relative(-1, &instructions)?;
+/// Verifies that the Ed25519 instruction at `ed25519_ix_index` carries a signature of `msg` by one
+/// of `pub_keys`. The instruction must precede the current one and may hold several signatures.
+pub fn verify_ed25519_ix(
+    instructions: &AccountInfo,
+    ed25519_ix_index: u16,
+    msg: &[u8],
+    pub_keys: &[[u8; 32]],
+) -> Result<()> {
+    let current_index = sysvar::load_current_index_checked(instructions)?;
+    if ed25519_ix_index >= current_index {
+        return Err(SignatureVerificationError::InvalidInstructionIndex.into());
+    }
+    let verify_instruction = sysvar::load_instruction_at_checked(ed25519_ix_index as usize, instructions)?;
 
     // Ensure the instruction is from the ed25519 program and has no accounts
     if verify_instruction.program_id != ed25519_program::ID
-        || verify_instruction.accounts.len() != 0
+        || !verify_instruction.accounts.is_empty()
     {
         msg!("Accounts length: {:?}", verify_instruction.accounts.len());
         return Err(SignatureVerificationError::NotSigVerified.into());
     }
 
-    // Calculate the expected end of the signature offsets data
-    let data_end = SIGNATURE_OFFSETS_START.saturating_add(SIGNATURE_OFFSETS_SERIALIZED_SIZE + 2);
-    if verify_instruction.data.len() < data_end {
-        return Err(SignatureVerificationError::LessDataThanExpected.into());
+    let signatures = parse_ed25519_ix(&verify_instruction, ed25519_ix_index)?;
+    let mut signer_found = false;
+    for (pubkey, message_data) in signatures {
+        if !pub_keys.contains(&pubkey) {
+            continue;
+        }
+        if message_data == msg {
+            return Ok(());
+        }
+        signer_found = true;
     }
 
-    // Extract the signature offsets data from the instruction
-    let data = &verify_instruction.data[SIGNATURE_OFFSETS_START..data_end];
-
-    // Deserialize the Ed25519 signature offsets
-    let ed25519_offsets = Ed25519SignatureOffsets {
-        signature_offset: u16::from_le_bytes([data[2], data[3]]),
-        signature_instruction_index: u16::from_le_bytes([data[4], data[5]]),
-        public_key_offset: u16::from_le_bytes([data[6], data[7]]),
-        public_key_instruction_index: u16::from_le_bytes([data[8], data[9]]),
-        message_data_offset: u16::from_le_bytes([data[10], data[11]]),
-        message_data_size: u16::from_le_bytes([data[12], data[13]]),
-        message_instruction_index: u16::from_le_bytes([data[14], data[15]]),
-    };
+    if signer_found {
+        Err(SignatureVerificationError::InvalidMessageData.into())
+    } else {
+        Err(SignatureVerificationError::InvalidSignature.into())
+    }
+}
 
-    // Validate the public key, signature, and message data offsets
-    let expected_pk_offset =
-        (SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SERIALIZED_SIZE + 2) as u16;
-    if ed25519_offsets.public_key_offset != expected_pk_offset
-        || ed25519_offsets.signature_offset
-            != ed25519_offsets.public_key_offset + PUBKEY_SERIALIZED_SIZE as u16
-        || ed25519_offsets.message_data_offset
-            != ed25519_offsets.signature_offset + SIGNATURE_SERIALIZED_SIZE as u16
-
-        || (ed25519_offsets.public_key_offset as usize + PUBKEY_SERIALIZED_SIZE - 1)
-            > verify_instruction.data.len()
-        || (ed25519_offsets.message_data_offset + ed25519_offsets.message_data_size - 1) as usize
-            > verify_instruction.data.len()
-    {
-        return Err(SignatureVerificationError::InvalidSignatureData.into());
+/// Verifies that at least `threshold` distinct keys of `signer_set` signed `msg`, looking at every
+/// Ed25519 program instruction that precedes the current one.
+pub fn verify_ed25519_threshold(
+    instructions: &AccountInfo,
+    msg: &[u8],
+    signer_set: &[[u8; 32]],
+    threshold: u8,
+) -> Result<()> {
+    let current_index = sysvar::load_current_index_checked(instructions)?;
+    let mut signed = vec![false; signer_set.len()];
+
+    for index in 0..current_index {
+        let instruction = sysvar::load_instruction_at_checked(index as usize, instructions)?;
+        if instruction.program_id != ed25519_program::ID || !instruction.accounts.is_empty() {
+            continue;
+        }
+        // Ed25519 instructions in a format we do not produce are not ours to reject
+        let Ok(signatures) = parse_ed25519_ix(&instruction, index) else {
+            continue;
+        };
+        for (pubkey, message_data) in signatures {
+            if message_data != msg {
+                continue;
+            }
+            if let Some(position) = signer_set.iter().position(|key| *key == pubkey) {
+                signed[position] = true;
+            }
+        }
     }
 
-    // Validate that all instruction indices are the same
-    if ed25519_offsets.signature_instruction_index != ed25519_offsets.public_key_instruction_index
-        || ed25519_offsets.signature_instruction_index != ed25519_offsets.message_instruction_index
-    {
-        return Err(SignatureVerificationError::InvalidSignatureData.into());
+    let signatures = signed.iter().filter(|signed| **signed).count();
+    if signatures < threshold as usize {
+        msg!("Signatures: {:?}, threshold: {:?}", signatures, threshold);
+        return Err(SignatureVerificationError::ThresholdNotMet.into());
+    }
+
+    Ok(())
+}
+
+/// Verifies a quote signature against the signer set when one is configured, otherwise against
+/// the signer key (and the previous signer key during a rotation) in the instruction at
+/// `ed25519_ix_index`.
+pub fn verify_quote_signature(
+    instructions: &AccountInfo,
+    ed25519_ix_index: u16,
+    msg: &[u8],
+    config: &Config,
+    now: u64,
+) -> Result<()> {
+    if config.signer_threshold > 0 {
+        verify_ed25519_threshold(instructions, msg, config.signer_set(), config.signer_threshold)
+    } else {
+        verify_ed25519_ix(instructions, ed25519_ix_index, msg, &config.signer_keys(now))
     }
+}
 
-    // Extract the public key, signature, and message data from the instruction
-    let pubkey = Pubkey::try_from(
-        &verify_instruction.data[ed25519_offsets.public_key_offset as usize
-            ..ed25519_offsets.public_key_offset as usize + PUBKEY_SERIALIZED_SIZE],
-    )
-        .map_err(|_| SignatureVerificationError::InvalidSignatureData)?;
-    if pubkey.to_bytes() != pub_key {
-        return Err(SignatureVerificationError::InvalidSignature.into());
+/// Extracts the (public key, message) pair of every signature in an Ed25519 instruction. Only
+/// signatures whose data lives in the instruction itself are accepted, since that is the data we
+/// compare against.
+fn parse_ed25519_ix(verify_instruction: &Instruction, own_index: u16) -> Result<Vec<([u8; 32], &[u8])>> {
+    let data = &verify_instruction.data;
+    if data.len() < SIGNATURE_OFFSETS_START {
+        return Err(SignatureVerificationError::LessDataThanExpected.into());
+    }
+    let num_signatures = data[0] as usize;
+    if num_signatures == 0 {
+        return Err(SignatureVerificationError::NotSigVerified.into());
     }
-    let message_data = &verify_instruction.data[
-        ed25519_offsets.message_data_offset as usize ..
-        (ed25519_offsets.message_data_offset + ed25519_offsets.message_data_size) as usize
-    ];
 
-    if message_data != msg {
-        return Err(SignatureVerificationError::InvalidMessageData.into());
+    // Calculate the expected end of the signature offsets table
+    let table_end = num_signatures
+        .checked_mul(SIGNATURE_OFFSETS_SERIALIZED_SIZE)
+        .and_then(|size| size.checked_add(SIGNATURE_OFFSETS_START))
+        .ok_or(SignatureVerificationError::InvalidSignatureData)?;
+    if data.len() < table_end {
+        return Err(SignatureVerificationError::LessDataThanExpected.into());
     }
 
-    Ok(())
+    let refers_to_self = |index: u16| index == CURRENT_INSTRUCTION_INDEX || index == own_index;
+    let slice = |offset: u16, size: usize| -> Result<&[u8]> {
+        let start = offset as usize;
+        let end = start.checked_add(size).ok_or(SignatureVerificationError::InvalidSignatureData)?;
+        data.get(start..end).ok_or(SignatureVerificationError::InvalidSignatureData.into())
+    };
+
+    let mut signatures = Vec::with_capacity(num_signatures);
+    for entry in data[SIGNATURE_OFFSETS_START..table_end].chunks_exact(SIGNATURE_OFFSETS_SERIALIZED_SIZE) {
+        // Deserialize the Ed25519 signature offsets
+        let ed25519_offsets = Ed25519SignatureOffsets {
+            signature_offset: u16::from_le_bytes([entry[0], entry[1]]),
+            signature_instruction_index: u16::from_le_bytes([entry[2], entry[3]]),
+            public_key_offset: u16::from_le_bytes([entry[4], entry[5]]),
+            public_key_instruction_index: u16::from_le_bytes([entry[6], entry[7]]),
+            message_data_offset: u16::from_le_bytes([entry[8], entry[9]]),
+            message_data_size: u16::from_le_bytes([entry[10], entry[11]]),
+            message_instruction_index: u16::from_le_bytes([entry[12], entry[13]]),
+        };
+
+        // Validate that the signature, public key and message all come from this instruction
+        if !refers_to_self(ed25519_offsets.signature_instruction_index)
+            || !refers_to_self(ed25519_offsets.public_key_instruction_index)
+            || !refers_to_self(ed25519_offsets.message_instruction_index)
+        {
+            return Err(SignatureVerificationError::InvalidSignatureData.into());
+        }
+
+        // Validate the public key, signature, and message data offsets
+        slice(ed25519_offsets.signature_offset, SIGNATURE_SERIALIZED_SIZE)?;
+        let pubkey = slice(ed25519_offsets.public_key_offset, PUBKEY_SERIALIZED_SIZE)?;
+        let message_data = slice(
+            ed25519_offsets.message_data_offset,
+            ed25519_offsets.message_data_size as usize,
+        )?;
+
+        let pubkey: [u8; 32] = pubkey
+            .try_into()
+            .map_err(|_| SignatureVerificationError::InvalidSignatureData)?;
+        signatures.push((pubkey, message_data));
+    }
+
+    Ok(signatures)
 }
@@ -1,5 +1,37 @@
-const BP: u64 = 10000;
+use anchor_lang::prelude::*;
+use crate::state::DomainConfig;
+
+pub const BP: u64 = 10000;
 
 pub fn calculate_fee(amount: u64, fee_bp: u64, fee_static: u64) -> u64 {
-    (fee_bp * amount) / BP + fee_static
+    let fee = (fee_bp as u128 * amount as u128) / BP as u128;
+    u64::try_from(fee).unwrap_or(u64::MAX).saturating_add(fee_static)
+}
+
+/// Fees of a transfer. `static_fee` and `gas_drop` are in lamports when the fee is paid natively,
+/// `net_amount` is what gets burned.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct FeeBreakdown {
+    pub percentage_fee: u64,
+    pub static_fee: u64,
+    pub gas_drop: u64,
+    pub net_amount: u64,
+}
+
+impl FeeBreakdown {
+    pub fn new(domain_config: &DomainConfig, amount: u64, fee: u64, gas_drop_amount: u64, fee_is_native: bool) -> Self {
+        let percentage_fee = domain_config.percentage_fee(amount);
+        let usdc_static_fee = if fee_is_native { 0 } else { fee };
+        FeeBreakdown {
+            percentage_fee,
+            static_fee: fee,
+            gas_drop: gas_drop_amount,
+            net_amount: amount.saturating_sub(percentage_fee.saturating_add(usdc_static_fee)),
+        }
+    }
+
+    /// Part of the fee taken out of the transferred USDC amount, `None` when it overflows.
+    pub fn usdc_fee(&self, fee_is_native: bool) -> Option<u64> {
+        self.percentage_fee.checked_add(if fee_is_native { 0 } else { self.static_fee })
+    }
 }
@@ -1,5 +1,7 @@
 pub mod ed25519;
 pub mod fee;
+pub mod pyth;
 
-pub use ed25519::verify_ed25519_ix;
-pub use fee::calculate_fee;
+pub use ed25519::{verify_ed25519_ix, verify_quote_signature};
+pub use fee::{calculate_fee, FeeBreakdown, BP};
+pub use pyth::PythPrice;
@@ -0,0 +1,222 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{pubkey, pubkey::Pubkey},
+};
+use crate::errors::OracleError;
+use super::BP;
+
+/// Pyth Solana receiver program, owner of the `PriceUpdateV2` accounts.
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// Pyth SOL/USD price feed id.
+pub const SOL_USD_FEED_ID: [u8; 32] = [
+    0xef, 0x0d, 0x8b, 0x6f, 0xda, 0x2c, 0xeb, 0xa4, 0x1d, 0xa1, 0x5d, 0x40, 0x95, 0xd1, 0xda, 0x39,
+    0x2a, 0x0d, 0x2f, 0x8e, 0xd0, 0xc6, 0xc7, 0xbc, 0x0f, 0x4c, 0xfa, 0xc8, 0xc2, 0x80, 0xb5, 0x6d,
+];
+
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+const VERIFICATION_LEVEL_OFFSET: usize = 40; // after the discriminator and the write authority
+const VERIFICATION_LEVEL_FULL: u8 = 1; // `Partial` carries a signature count, `Full` nothing
+const PRICE_MESSAGE_SIZE: usize = 84; // feed id, price, conf, exponent, publish time, previous publish time, EMA price and conf
+
+// legacy push price account layout, only accepted on localnet to mock the oracle in tests
+#[cfg(feature = "localnet")]
+mod mock {
+    pub const MAGIC: u32 = 0xa1b2c3d4; // Pyth account magic number
+    pub const PRICE_ACCOUNT_TYPE: u32 = 3; // Account type of a Pyth price account
+    pub const STATUS_TRADING: u32 = 1; // Aggregate price status while the price is valid
+    pub const PRICE_ACCOUNT_MIN_SIZE: usize = 240; // Size up to the end of the aggregate price info
+
+    pub const MAGIC_OFFSET: usize = 0;
+    pub const ACCOUNT_TYPE_OFFSET: usize = 8;
+    pub const EXPO_OFFSET: usize = 20;
+    pub const TIMESTAMP_OFFSET: usize = 96;
+    pub const AGG_PRICE_OFFSET: usize = 208;
+    pub const AGG_CONF_OFFSET: usize = 216;
+    pub const AGG_STATUS_OFFSET: usize = 224;
+}
+
+/// SOL/USD price read from a Pyth `PriceUpdateV2` account. The account to read is pinned by
+/// address in the config, its owner, verification level and feed id are checked here.
+#[derive(Debug, Clone, Copy)]
+pub struct PythPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub timestamp: i64,
+}
+
+impl PythPrice {
+    pub fn load(account: &AccountInfo) -> Result<Self> {
+        let data = account.try_borrow_data()?;
+        if account.owner == &PYTH_RECEIVER_PROGRAM_ID {
+            return Self::parse_price_update(&data);
+        }
+        #[cfg(feature = "localnet")]
+        return Self::parse_mock(&data);
+        #[cfg(not(feature = "localnet"))]
+        err!(OracleError::InvalidPriceAccount)
+    }
+
+    pub fn parse_price_update(data: &[u8]) -> Result<Self> {
+        let message_offset = VERIFICATION_LEVEL_OFFSET + 1;
+        require!(data.len() >= message_offset + PRICE_MESSAGE_SIZE, OracleError::InvalidPriceAccount);
+        require!(data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR, OracleError::InvalidPriceAccount);
+        require!(data[VERIFICATION_LEVEL_OFFSET] == VERIFICATION_LEVEL_FULL, OracleError::PriceUnavailable);
+        require!(data[message_offset..message_offset + 32] == SOL_USD_FEED_ID, OracleError::InvalidPriceAccount);
+
+        Ok(PythPrice {
+            price: read_u64(data, message_offset + 32) as i64,
+            conf: read_u64(data, message_offset + 40),
+            expo: read_u32(data, message_offset + 48) as i32,
+            timestamp: read_u64(data, message_offset + 52) as i64,
+        })
+    }
+
+    #[cfg(feature = "localnet")]
+    pub fn parse_mock(data: &[u8]) -> Result<Self> {
+        use mock::*;
+        require!(data.len() >= PRICE_ACCOUNT_MIN_SIZE, OracleError::InvalidPriceAccount);
+        require!(read_u32(data, MAGIC_OFFSET) == MAGIC, OracleError::InvalidPriceAccount);
+        require!(read_u32(data, ACCOUNT_TYPE_OFFSET) == PRICE_ACCOUNT_TYPE, OracleError::InvalidPriceAccount);
+        require!(read_u32(data, AGG_STATUS_OFFSET) == STATUS_TRADING, OracleError::PriceUnavailable);
+
+        Ok(PythPrice {
+            price: read_u64(data, AGG_PRICE_OFFSET) as i64,
+            conf: read_u64(data, AGG_CONF_OFFSET),
+            expo: read_u32(data, EXPO_OFFSET) as i32,
+            timestamp: read_u64(data, TIMESTAMP_OFFSET) as i64,
+        })
+    }
+
+    /// Checks that the price is positive, at most `max_age` seconds old and that its confidence
+    /// interval is within `max_confidence_bp` of the price.
+    pub fn validate(&self, now: i64, max_age: u64, max_confidence_bp: u64) -> Result<()> {
+        require!(self.price > 0, OracleError::PriceUnavailable);
+        require!(
+            now.saturating_sub(self.timestamp) <= max_age as i64,
+            OracleError::StalePrice,
+        );
+        require!(
+            self.conf as u128 * BP as u128 <= self.price as u128 * max_confidence_bp as u128,
+            OracleError::PriceConfidenceTooWide,
+        );
+        Ok(())
+    }
+
+    /// Converts a micro-USDC amount into lamports, with the price quoted in USD per SOL.
+    pub fn usdc_to_lamports(&self, usdc_amount: u64) -> Result<u64> {
+        // lamports = usdc_amount * 10^(9 - 6) / (price * 10^expo)
+        let scale = 10u128.checked_pow(self.expo.unsigned_abs()).ok_or(OracleError::InvalidPriceAccount)?;
+        let (numerator, denominator) = if self.expo <= 0 {
+            ((usdc_amount as u128 * 1_000).checked_mul(scale), Some(self.price as u128))
+        } else {
+            (Some(usdc_amount as u128 * 1_000), (self.price as u128).checked_mul(scale))
+        };
+        numerator.zip(denominator)
+            .and_then(|(numerator, denominator)| u64::try_from(numerator / denominator).ok())
+            .ok_or(OracleError::InvalidPriceAccount.into())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL_PRICE: i64 = 150_00000000; // $150 with 8 decimals
+
+    fn price_update(verification_level: u8, feed_id: [u8; 32]) -> Vec<u8> {
+        let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[7; 32]); // write authority
+        data.push(verification_level);
+        data.extend_from_slice(&feed_id);
+        data.extend_from_slice(&SOL_PRICE.to_le_bytes());
+        data.extend_from_slice(&5_000_000u64.to_le_bytes()); // conf
+        data.extend_from_slice(&(-8i32).to_le_bytes());
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // publish time
+        data.extend_from_slice(&[0; 32]); // previous publish time, EMA price and conf
+        data.extend_from_slice(&0u64.to_le_bytes()); // posted slot
+        data
+    }
+
+    fn sol_price(price: i64, conf: u64, expo: i32) -> PythPrice {
+        PythPrice { price, conf, expo, timestamp: 1_700_000_000 }
+    }
+
+    #[test]
+    fn parses_price_update() {
+        let price = PythPrice::parse_price_update(&price_update(VERIFICATION_LEVEL_FULL, SOL_USD_FEED_ID)).unwrap();
+        assert_eq!(price.price, SOL_PRICE);
+        assert_eq!(price.conf, 5_000_000);
+        assert_eq!(price.expo, -8);
+        assert_eq!(price.timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn rejects_invalid_price_updates() {
+        let partial = price_update(0, SOL_USD_FEED_ID);
+        assert_eq!(PythPrice::parse_price_update(&partial).unwrap_err(), OracleError::PriceUnavailable.into());
+
+        let other_feed = price_update(VERIFICATION_LEVEL_FULL, [1; 32]);
+        assert_eq!(PythPrice::parse_price_update(&other_feed).unwrap_err(), OracleError::InvalidPriceAccount.into());
+
+        let mut other_account = price_update(VERIFICATION_LEVEL_FULL, SOL_USD_FEED_ID);
+        other_account[0] ^= 1;
+        assert_eq!(PythPrice::parse_price_update(&other_account).unwrap_err(), OracleError::InvalidPriceAccount.into());
+
+        let truncated = &price_update(VERIFICATION_LEVEL_FULL, SOL_USD_FEED_ID)[..100];
+        assert_eq!(PythPrice::parse_price_update(truncated).unwrap_err(), OracleError::InvalidPriceAccount.into());
+    }
+
+    #[test]
+    fn validates_price() {
+        let price = sol_price(SOL_PRICE, 15_000_000, -8); // 0.1% confidence
+        assert!(price.validate(1_700_000_060, 60, 10).is_ok());
+        assert_eq!(price.validate(1_700_000_061, 60, 10).unwrap_err(), OracleError::StalePrice.into());
+        assert_eq!(price.validate(1_700_000_000, 60, 9).unwrap_err(), OracleError::PriceConfidenceTooWide.into());
+        assert_eq!(sol_price(0, 0, -8).validate(1_700_000_000, 60, 10).unwrap_err(), OracleError::PriceUnavailable.into());
+    }
+
+    #[test]
+    fn converts_usdc_to_lamports() {
+        // 1 USDC at $150 per SOL
+        assert_eq!(sol_price(SOL_PRICE, 0, -8).usdc_to_lamports(1_000_000).unwrap(), 6_666_666);
+        assert_eq!(sol_price(150, 0, 0).usdc_to_lamports(1_000_000).unwrap(), 6_666_666);
+        assert_eq!(sol_price(15, 0, 1).usdc_to_lamports(1_000_000).unwrap(), 6_666_666);
+        assert_eq!(sol_price(SOL_PRICE, 0, -8).usdc_to_lamports(0).unwrap(), 0);
+
+        assert_eq!(sol_price(SOL_PRICE, 0, -40).usdc_to_lamports(1_000_000).unwrap_err(), OracleError::InvalidPriceAccount.into());
+        assert_eq!(sol_price(1, 0, -8).usdc_to_lamports(u64::MAX).unwrap_err(), OracleError::InvalidPriceAccount.into());
+    }
+
+    #[cfg(feature = "localnet")]
+    #[test]
+    fn parses_mock_price() {
+        use mock::*;
+        let mut data = vec![0; PRICE_ACCOUNT_MIN_SIZE];
+        data[MAGIC_OFFSET..MAGIC_OFFSET + 4].copy_from_slice(&MAGIC.to_le_bytes());
+        data[ACCOUNT_TYPE_OFFSET..ACCOUNT_TYPE_OFFSET + 4].copy_from_slice(&PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[EXPO_OFFSET..EXPO_OFFSET + 4].copy_from_slice(&(-8i32).to_le_bytes());
+        data[TIMESTAMP_OFFSET..TIMESTAMP_OFFSET + 8].copy_from_slice(&1_700_000_000i64.to_le_bytes());
+        data[AGG_PRICE_OFFSET..AGG_PRICE_OFFSET + 8].copy_from_slice(&SOL_PRICE.to_le_bytes());
+        data[AGG_CONF_OFFSET..AGG_CONF_OFFSET + 8].copy_from_slice(&5_000_000u64.to_le_bytes());
+        data[AGG_STATUS_OFFSET..AGG_STATUS_OFFSET + 4].copy_from_slice(&STATUS_TRADING.to_le_bytes());
+
+        let price = PythPrice::parse_mock(&data).unwrap();
+        assert_eq!(price.price, SOL_PRICE);
+        assert_eq!(price.conf, 5_000_000);
+        assert_eq!(price.expo, -8);
+        assert_eq!(price.timestamp, 1_700_000_000);
+        assert_eq!(price.usdc_to_lamports(1_000_000).unwrap(), 6_666_666);
+
+        data[AGG_STATUS_OFFSET] = 0;
+        assert_eq!(PythPrice::parse_mock(&data).unwrap_err(), OracleError::PriceUnavailable.into());
+    }
+}